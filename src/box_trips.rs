@@ -0,0 +1,211 @@
+use serde::{Serialize, Deserialize};
+use serde_json::{json, Value};
+
+use crate::{game_stats::GameStats, game_model::{Game, BoxTrip}};
+
+#[derive(Serialize, Deserialize, PartialEq)]
+struct SkaterBoxTrips {
+    #[serde(rename = "name")]
+    name: String,
+
+    #[serde(rename = "team")]
+    team: u8,
+
+    #[serde(flatten)]
+    totals: BoxTripTotals,
+}
+
+#[derive(Serialize, Deserialize, PartialEq)]
+struct TeamBoxTrips {
+    #[serde(rename = "team")]
+    team: u8,
+
+    #[serde(flatten)]
+    totals: BoxTripTotals,
+}
+
+#[derive(Serialize, Deserialize, PartialEq)]
+struct BoxTripTotals {
+    #[serde(rename = "tripCount")]
+    trip_count: usize,
+
+    #[serde(rename = "totalTime")]
+    total_time: f32,
+
+    #[serde(rename = "meanTripLength")]
+    mean_trip_length: Option<f32>,
+
+    #[serde(rename = "multiJamTripCount")]
+    multi_jam_trip_count: usize,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Default)]
+struct BoxTripStates {
+    #[serde(rename = "skaters")]
+    skaters: Vec<SkaterBoxTrips>,
+
+    #[serde(rename = "teams")]
+    teams: Vec<TeamBoxTrips>,
+}
+
+impl BoxTripTotals {
+    fn from_box_trips<'a>(box_trips: impl Iterator<Item = &'a BoxTrip>) -> BoxTripTotals {
+        let box_trips: Vec<&BoxTrip> = box_trips.collect();
+
+        let durations: Vec<f32> = box_trips.iter()
+            .filter_map(|box_trip| box_trip.get_duration())
+            .map(|duration| duration as f32 / 1000.0)
+            .collect();
+
+        let total_time = durations.iter().fold(0.0, |total, duration| total + duration);
+
+        let mean_trip_length = if durations.is_empty() {
+            None
+        } else {
+            Some(total_time / durations.len() as f32)
+        };
+
+        BoxTripTotals {
+            trip_count: box_trips.len(),
+            total_time,
+            mean_trip_length,
+            multi_jam_trip_count: box_trips.iter().filter(|box_trip| box_trip.spans_multiple_jams()).count(),
+        }
+    }
+}
+
+pub struct BoxTrips;
+
+impl BoxTrips {
+    fn get_game_stats(game: &Game) -> BoxTripStates {
+        let mut skaters: Vec<SkaterBoxTrips> = game.teams.iter()
+            .flat_map(|(team, team_details)| team_details.skaters.iter().map(move |(skater_id, skater)| (*team, team_details, skater_id, skater)))
+            .filter(|(_, _, _, skater)| !skater.name.is_empty())
+            .map(|(team, team_details, skater_id, skater)| SkaterBoxTrips {
+                name: skater.name.clone(),
+                team,
+                totals: BoxTripTotals::from_box_trips(team_details.box_trips.values()
+                    .filter(|box_trip| box_trip.skater_id.as_ref() == Some(skater_id))),
+            })
+            .filter(|stats| stats.totals.trip_count > 0)
+            .collect();
+
+        skaters.sort_by(|a, b| a.team.cmp(&b.team).then_with(|| a.name.cmp(&b.name)));
+
+        let teams = game.teams.iter()
+            .map(|(team, team_details)| TeamBoxTrips {
+                team: *team,
+                totals: BoxTripTotals::from_box_trips(team_details.box_trips.values()),
+            })
+            .collect();
+
+        BoxTripStates { skaters, teams }
+    }
+}
+
+impl GameStats for BoxTrips {
+    const DATA_TYPE: &'static str = "BoxTrips";
+    const TOPICS: &'static [&'static str] = &[
+        "ScoreBoard.Game(*).Team(*).Name",
+        "ScoreBoard.Game(*).Team(*).Skater(*).Name",
+        "ScoreBoard.Game(*).Team(*).BoxTrip(*).CurrentSkater",
+        "ScoreBoard.Game(*).Team(*).BoxTrip(*).StartPeriodNumber",
+        "ScoreBoard.Game(*).Team(*).BoxTrip(*).StartJamNumber",
+        "ScoreBoard.Game(*).Team(*).BoxTrip(*).EndPeriodNumber",
+        "ScoreBoard.Game(*).Team(*).BoxTrip(*).EndJamNumber",
+        "ScoreBoard.Game(*).Team(*).BoxTrip(*).WalltimeStart",
+        "ScoreBoard.Game(*).Team(*).BoxTrip(*).WalltimeEnd",
+    ];
+
+    fn compute_game(game: &Game) -> Value {
+        json!(Self::get_game_stats(game))
+    }
+
+    fn get_empty_state() -> Value {
+        json!(BoxTripStates::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::{game_model::GameModel, scoreboard_path::ScoreboardPath};
+
+    fn get_game(entries: &[(&str, Value)]) -> Game {
+        let mut model = GameModel::default();
+
+        for (key, value) in entries {
+            model.apply_change(&ScoreboardPath::parse(key).unwrap(), value).unwrap();
+        }
+
+        model.games.remove("g1").unwrap()
+    }
+
+    fn get_box_trip_entries() -> Vec<(&'static str, Value)> {
+        vec![
+            ("ScoreBoard.Game(g1).Team(1).Skater(h1).Name", json!("Home Blocker")),
+            ("ScoreBoard.Game(g1).Team(1).Skater(h2).Name", json!("Home Jammer")),
+            ("ScoreBoard.Game(g1).Team(1).BoxTrip(b1).CurrentSkater", json!("h1")),
+            ("ScoreBoard.Game(g1).Team(1).BoxTrip(b1).StartPeriodNumber", json!(1)),
+            ("ScoreBoard.Game(g1).Team(1).BoxTrip(b1).StartJamNumber", json!(1)),
+            ("ScoreBoard.Game(g1).Team(1).BoxTrip(b1).EndPeriodNumber", json!(1)),
+            ("ScoreBoard.Game(g1).Team(1).BoxTrip(b1).EndJamNumber", json!(1)),
+            ("ScoreBoard.Game(g1).Team(1).BoxTrip(b1).WalltimeStart", json!(100_000)),
+            ("ScoreBoard.Game(g1).Team(1).BoxTrip(b1).WalltimeEnd", json!(130_000)),
+            ("ScoreBoard.Game(g1).Team(1).BoxTrip(b2).CurrentSkater", json!("h1")),
+            ("ScoreBoard.Game(g1).Team(1).BoxTrip(b2).StartPeriodNumber", json!(1)),
+            ("ScoreBoard.Game(g1).Team(1).BoxTrip(b2).StartJamNumber", json!(3)),
+            ("ScoreBoard.Game(g1).Team(1).BoxTrip(b2).EndPeriodNumber", json!(1)),
+            ("ScoreBoard.Game(g1).Team(1).BoxTrip(b2).EndJamNumber", json!(4)),
+            ("ScoreBoard.Game(g1).Team(1).BoxTrip(b2).WalltimeStart", json!(400_000)),
+            ("ScoreBoard.Game(g1).Team(1).BoxTrip(b2).WalltimeEnd", json!(445_000)),
+        ]
+    }
+
+    fn get_totals(totals: &BoxTripTotals) -> (usize, f32, Option<f32>, usize) {
+        (totals.trip_count, totals.total_time, totals.mean_trip_length, totals.multi_jam_trip_count)
+    }
+
+    #[test]
+    fn computes_trip_durations() {
+        let stats = BoxTrips::get_game_stats(&get_game(&get_box_trip_entries()));
+
+        assert_eq!(stats.skaters.len(), 1);
+        assert_eq!(stats.skaters[0].name, "Home Blocker");
+        assert_eq!(get_totals(&stats.skaters[0].totals), (2, 75.0, Some(37.5), 1));
+    }
+
+    #[test]
+    fn counts_trips_spanning_jams_across_periods() {
+        let mut entries = get_box_trip_entries();
+        entries.extend([
+            ("ScoreBoard.Game(g1).Team(1).BoxTrip(b1).EndPeriodNumber", json!(2)),
+            ("ScoreBoard.Game(g1).Team(1).BoxTrip(b1).EndJamNumber", json!(1)),
+        ]);
+
+        let stats = BoxTrips::get_game_stats(&get_game(&entries));
+        assert_eq!(get_totals(&stats.teams[0].totals), (2, 75.0, Some(37.5), 2));
+    }
+
+    #[test]
+    fn counts_open_trips_without_time() {
+        let mut entries = get_box_trip_entries();
+        entries.extend([
+            ("ScoreBoard.Game(g1).Team(1).BoxTrip(b3).CurrentSkater", json!("h2")),
+            ("ScoreBoard.Game(g1).Team(1).BoxTrip(b3).StartPeriodNumber", json!(1)),
+            ("ScoreBoard.Game(g1).Team(1).BoxTrip(b3).StartJamNumber", json!(5)),
+            ("ScoreBoard.Game(g1).Team(1).BoxTrip(b3).EndPeriodNumber", json!(0)),
+            ("ScoreBoard.Game(g1).Team(1).BoxTrip(b3).EndJamNumber", json!(0)),
+            ("ScoreBoard.Game(g1).Team(1).BoxTrip(b3).WalltimeStart", json!(500_000)),
+            ("ScoreBoard.Game(g1).Team(1).BoxTrip(b3).WalltimeEnd", json!(0)),
+        ]);
+
+        let stats = BoxTrips::get_game_stats(&get_game(&entries));
+
+        assert_eq!(stats.skaters[1].name, "Home Jammer");
+        assert_eq!(get_totals(&stats.skaters[1].totals), (1, 0.0, None, 0));
+        assert_eq!(get_totals(&stats.teams[0].totals), (3, 75.0, Some(37.5), 1));
+    }
+}
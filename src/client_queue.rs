@@ -0,0 +1,224 @@
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+
+use tokio::sync::Notify;
+
+use serde_json::Value;
+
+use crate::{socket_server::{Update, SubscriptionKey, UpdateMode}, server_diagnostics::ServerDiagnostics, json_patch};
+
+const MAX_QUEUED_RESPONSES: usize = 100;
+
+#[derive(Default)]
+struct QueueState {
+    responses: VecDeque<Value>,
+    order: VecDeque<SubscriptionKey>,
+    updates: HashMap<SubscriptionKey, Update>,
+    last_patched_values: HashMap<SubscriptionKey, Option<Value>>,
+    is_closed: bool,
+}
+
+pub enum QueuedMessage {
+    Update(Update),
+    Patch(Update),
+    Response(Value),
+}
+
+pub struct ClientQueue {
+    state: Mutex<QueueState>,
+    notify: Notify,
+    diagnostics: ServerDiagnostics,
+}
+
+impl ClientQueue {
+    pub fn new(diagnostics: ServerDiagnostics) -> ClientQueue {
+        ClientQueue {
+            state: Mutex::new(QueueState::default()),
+            notify: Notify::new(),
+            diagnostics,
+        }
+    }
+
+    pub fn push(&self, update: Update) {
+        let mut state = self.state.lock().unwrap();
+
+        if state.is_closed {
+            return;
+        }
+
+        let key = (update.game_id.clone(), update.data_type.clone());
+
+        if state.updates.insert(key.clone(), update).is_some() {
+            self.diagnostics.update_coalesced();
+        } else {
+            state.order.push_back(key);
+        }
+
+        self.diagnostics.update_queued();
+        self.notify.notify_one();
+    }
+
+    pub fn push_response(&self, response: Value) {
+        let mut state = self.state.lock().unwrap();
+
+        if state.is_closed {
+            return;
+        }
+
+        if state.responses.len() >= MAX_QUEUED_RESPONSES {
+            self.diagnostics.response_dropped();
+            return;
+        }
+
+        state.responses.push_back(response);
+
+        self.notify.notify_one();
+    }
+
+    pub async fn next(&self) -> Option<QueuedMessage> {
+        loop {
+            {
+                let mut state = self.state.lock().unwrap();
+
+                if state.is_closed {
+                    return None;
+                }
+
+                if let Some(response) = state.responses.pop_front() {
+                    return Some(QueuedMessage::Response(response));
+                }
+
+                while let Some(key) = state.order.pop_front() {
+                    let update = match state.updates.remove(&key) {
+                        Some(u) => u,
+                        None => continue,
+                    };
+
+                    if let Some(message) = Self::get_update_message(&mut state, &key, update) {
+                        return Some(message);
+                    }
+                }
+            }
+
+            self.notify.notified().await;
+        }
+    }
+
+    pub fn set_update_mode(&self, key: &SubscriptionKey, update_mode: UpdateMode) {
+        let mut state = self.state.lock().unwrap();
+
+        match update_mode {
+            UpdateMode::Full => state.last_patched_values.remove(key),
+            UpdateMode::Patch => state.last_patched_values.insert(key.clone(), None),
+        };
+    }
+
+    pub fn clear_update_modes(&self) {
+        self.state.lock().unwrap().last_patched_values.clear();
+    }
+
+    pub fn close(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.is_closed = true;
+        state.order.clear();
+        state.responses.clear();
+        state.updates.clear();
+        state.last_patched_values.clear();
+
+        self.notify.notify_one();
+    }
+
+    fn get_update_message(state: &mut QueueState, key: &SubscriptionKey, mut update: Update) -> Option<QueuedMessage> {
+        let last_value = match state.last_patched_values.get_mut(key) {
+            Some(v) => v,
+            None => return Some(QueuedMessage::Update(update)),
+        };
+
+        let previous_value = last_value.replace(update.update.clone());
+
+        let previous_value = match previous_value {
+            Some(v) => v,
+            None => return Some(QueuedMessage::Update(update)),
+        };
+
+        let operations = json_patch::diff(&previous_value, &update.update);
+
+        if operations.is_empty() {
+            return None;
+        }
+
+        update.update = Value::Array(operations);
+
+        Some(QueuedMessage::Patch(update))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use serde_json::json;
+
+    use super::*;
+
+    fn get_key() -> SubscriptionKey {
+        ("g1".to_string(), "TestStats".to_string())
+    }
+
+    fn get_update(value: Value) -> Update {
+        Update { game_id: "g1".to_string(), data_type: "TestStats".to_string(), update: value }
+    }
+
+    async fn next_message(queue: &ClientQueue) -> Option<QueuedMessage> {
+        tokio::time::timeout(Duration::from_millis(50), queue.next()).await.ok().flatten()
+    }
+
+    async fn next_update(queue: &ClientQueue) -> Value {
+        match next_message(queue).await {
+            Some(QueuedMessage::Update(update)) => update.update,
+            _ => panic!("Expected a full update"),
+        }
+    }
+
+    async fn next_patch(queue: &ClientQueue) -> Value {
+        match next_message(queue).await {
+            Some(QueuedMessage::Patch(update)) => update.update,
+            _ => panic!("Expected a patch"),
+        }
+    }
+
+    #[tokio::test]
+    async fn patch_mode_sends_full_state_then_patches() {
+        let queue = ClientQueue::new(ServerDiagnostics::default());
+        queue.set_update_mode(&get_key(), UpdateMode::Patch);
+
+        queue.push(get_update(json!({ "jams": [1] })));
+        assert_eq!(next_update(&queue).await, json!({ "jams": [1] }));
+
+        queue.push(get_update(json!({ "jams": [1, 2] })));
+        assert_eq!(next_patch(&queue).await, json!([{ "op": "add", "path": "/jams/1", "value": 2 }]));
+
+        queue.push(get_update(json!({ "jams": [1, 2] })));
+        assert!(next_message(&queue).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn resends_full_state_after_update_modes_are_cleared() {
+        let queue = ClientQueue::new(ServerDiagnostics::default());
+        queue.set_update_mode(&get_key(), UpdateMode::Patch);
+
+        queue.push(get_update(json!({ "jams": [1] })));
+        next_update(&queue).await;
+
+        queue.clear_update_modes();
+        queue.push(get_update(json!({ "jams": [1, 2] })));
+        assert_eq!(next_update(&queue).await, json!({ "jams": [1, 2] }));
+
+        queue.set_update_mode(&get_key(), UpdateMode::Patch);
+        queue.push(get_update(json!({ "jams": [1, 2, 3] })));
+        assert_eq!(next_update(&queue).await, json!({ "jams": [1, 2, 3] }));
+
+        queue.push(get_update(json!({ "jams": [1, 2] })));
+        assert_eq!(next_patch(&queue).await, json!([{ "op": "remove", "path": "/jams/2" }]));
+    }
+}
@@ -3,13 +3,12 @@ use std::{collections::HashMap, sync::Arc};
 use tokio::sync::Mutex;
 
 use log::{debug, error};
-use regex::Regex;
 use serde::{Serialize, Deserialize};
 use serde_json::{json, Value};
 
-use crate::{socket_server::{UpdateProvider, Update, SocketServer}, scoreboard_connector::{ScoreboardConnection, ScoreboardState}};
+use crate::{socket_server::{UpdateProvider, Update, SocketServer}, scoreboard_connector::ScoreboardStateChange, scoreboard_source::ScoreboardSource, game_model as model};
 
-#[derive(Serialize, Deserialize, Clone)]
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
 struct Team {
     #[serde(rename = "name")]
     pub name: String,
@@ -18,7 +17,7 @@ struct Team {
     pub color: String,
 }
 
-#[derive(Serialize, Deserialize, Clone)]
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
 struct Game {
     #[serde(rename = "id")]
     pub id: String,
@@ -37,84 +36,58 @@ struct Game {
 }
 
 impl Game {
-    pub fn new(id: String) -> Game {
-        Game {
-            id,
-            start_time: 0,
-            is_current: false,
-            home_team: Team {
-                name: "".to_string(),
-                color: "".to_string(),
-            },
-            away_team: Team {
+    fn from_model(game: &model::Game, is_current: bool) -> Game {
+        let get_team = |team_number| game.teams.get(&team_number)
+            .map(|team: &model::Team| Team {
+                name: team.name.clone(),
+                color: team.color.clone(),
+            })
+            .unwrap_or(Team {
                 name: "".to_string(),
                 color: "".to_string(),
-            },
+            });
+
+        Game {
+            id: game.id.clone(),
+            start_time: game.periods.get(&1).and_then(|p| p.walltime_start).unwrap_or(0),
+            is_current,
+            home_team: get_team(1),
+            away_team: get_team(2),
         }
     }
 }
 
 pub struct GameInfo {
-    games: Vec<Game>,
-    current_game_regex: Regex,
-    team_regex: Regex,
-    period_start_regex: Regex,
-}
-
-#[derive(Clone)]
-enum Match {
-    CurrentGame(CurrentGameMatches),
-    Team(TeamMatches),
-    PeriodStart(PeriodStartMatches),
-}
-
-#[derive(Clone)]
-struct CurrentGameMatches {
-    game_id: String,
-}
-
-#[derive(Clone)]
-struct TeamMatches {
-    game_id: String,
-    team_id: u8,
-    property_name: String,
-    value: String,
-}
-
-#[derive(Clone)]
-struct PeriodStartMatches {
-    game_id: String,
-    start_time: u64,
+    games: HashMap<String, Game>,
 }
 
 impl GameInfo {
-    pub async fn new(scoreboard: &mut ScoreboardConnection, socket_server: &mut SocketServer) {
+    pub async fn register(scoreboard: &mut dyn ScoreboardSource, socket_server: &mut SocketServer) {
         let game_info = Arc::new(Mutex::new(GameInfo { 
-            games: Vec::new(),
-            current_game_regex: Regex::new(r#"^ScoreBoard\.CurrentGame\.Game$"#).unwrap(),
-            team_regex: Regex::new(r#"^ScoreBoard\.Game\(([^\)]+)\)\.Team\((\d+)\)\.([^\.]+)$"#).unwrap(),
-            period_start_regex: Regex::new(r#"^ScoreBoard\.Game\(([^\)]+)\)\.Period\(1\)\.WalltimeStart$"#).unwrap(),
+            games: HashMap::new(),
         }));
 
         let mut receiver = scoreboard.get_receiver();
 
         let update_sender = socket_server.get_update_sender();
-        socket_server.register_update_provider(&"Games".to_string(), game_info.clone()).await;
+        socket_server.register_update_provider("Games", game_info.clone()).await;
 
         tokio::task::spawn(async move {
-            while let Ok(state_update) = receiver.recv().await {
+            while let Some(state_update) = receiver.recv().await {
                 let mut locked_games = game_info.lock().await;
 
-                locked_games.process_state_update(state_update);
+                if !locked_games.process_state_update(state_update) {
+                    continue;
+                }
 
                 let update = Update { 
                     game_id: "*".to_string(),
                     data_type: "Games".to_string(),
-                    update: json!(locked_games.games)
+                    update: locked_games.get_games_json()
                 };
 
                 debug!("Sending Games update");
-                if let Err(e) = update_sender.send(update) {
+                if let Err(e) = update_sender.send(update).await {
                     error!("Error sending update on mpsc: {:?}", e);
                 }
             }
@@ -126,85 +99,42 @@ impl GameInfo {
         scoreboard.register_topic("ScoreBoard.Game(*).Period(1).WalltimeStart");
     }
 
-    fn process_state_update(&mut self, update: ScoreboardState) {
+    fn process_state_update(&mut self, change: ScoreboardStateChange) -> bool {
         debug!("Processing stats update for games");
 
-        let games = update.iter()
-            .filter_map(|s| self.get_relevant_states(s))
-            .fold(HashMap::new(), |mut map, match_info| {
-                let game_id = match match_info.clone() {
-                    Match::CurrentGame(current_game) => current_game.game_id,
-                    Match::Team(team) => team.game_id,
-                    Match::PeriodStart(period_start) => period_start.game_id,
-                };
+        if change.updated_game_ids.is_empty() && !change.is_reset {
+            return false;
+        }
 
-                if !map.contains_key(&game_id) {
-                    map.insert(game_id.clone(), Game::new(game_id.clone()));
-                }
+        let model = change.model.read();
 
-                let game = map.get_mut(&game_id).unwrap();
-
-                match match_info {
-                    Match::CurrentGame(current) => {
-                        game.is_current = true;
-                        game.id = current.game_id;
-                    },
-                    Match::Team(team_match) => {
-                        let team = if team_match.team_id == 1 { &mut game.home_team } else { &mut game.away_team };
-                        match team_match.property_name.as_str() {
-                            "Name" => {
-                                team.name = team_match.value;
-                            },
-                            "UniformColor" => {
-                                team.color = team_match.value;
-                            },
-                            _ => { }
-                        };
-                    },
-                    Match::PeriodStart(period_start) => {
-                        game.start_time = period_start.start_time;
-                    },
-                }
+        let games: HashMap<String, Game> = model.games.iter()
+            .map(|(game_id, game)| {
+                let is_current = model.current_game_id.as_ref() == Some(game_id);
+                (game_id.clone(), Game::from_model(game, is_current))
+            })
+            .collect();
 
-                map
-            });
+        if games == self.games {
+            return false;
+        }
+
+        self.games = games;
 
-        self.games = games.values().cloned().collect();
+        true
     }
 
-    fn get_relevant_states(&self, (key, value): (&String, &Value)) -> Option<Match> {
-        if self.current_game_regex.is_match(key) {
-            Some(Match::CurrentGame(CurrentGameMatches {
-                game_id: value.as_str().unwrap().to_string(),
-            }))
-        } else if self.team_regex.is_match(key) {
-            self.team_regex.captures(key).map(|c| {
-                let (_, [game_id, team_id, property_name]) = c.extract();
-
-                Match::Team(TeamMatches { 
-                    game_id: game_id.to_string(),
-                    team_id: team_id.parse::<u8>().unwrap(),
-                    property_name: property_name.to_string(), 
-                    value: value.as_str().unwrap().to_string(),
-                })
-            })
-        } else if self.period_start_regex.is_match(key) {
-            self.period_start_regex.captures(key).map(|c| {
-                let (_, [game_id]) = c.extract();
-
-                Match::PeriodStart(PeriodStartMatches { 
-                    game_id: game_id.to_string(),
-                    start_time: value.as_u64().unwrap(),
-                })
-            })
-        } else {
-            None
-        }
+    fn get_games_json(&self) -> Value {
+        json!(self.games.values().collect::<Vec<&Game>>())
     }
 }
 
 impl UpdateProvider for GameInfo {
-    fn get_state(&self, _game_id: &String) -> serde_json::Value {
-        json!(self.games)
+    fn get_state(&self, _game_id: &str) -> Option<serde_json::Value> {
+        Some(self.get_games_json())
+    }
+
+    fn get_empty_state(&self) -> serde_json::Value {
+        json!([])
     }
 }
@@ -0,0 +1,609 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt;
+use std::sync::{Arc, RwLock, RwLockReadGuard};
+
+use log::debug;
+use serde_json::Value;
+
+use crate::scoreboard_path::{ScoreboardPath, FieldingPosition};
+
+#[derive(Default)]
+pub struct GameModel {
+    pub current_game_id: Option<String>,
+    pub games: HashMap<String, Game>,
+}
+
+#[derive(Clone, Default)]
+pub struct Game {
+    pub id: String,
+    pub penalty_codes: BTreeMap<String, String>,
+    pub teams: BTreeMap<u8, Team>,
+    pub periods: BTreeMap<u8, Period>,
+}
+
+#[derive(Clone, Default)]
+pub struct Team {
+    pub name: String,
+    pub color: String,
+    pub skaters: HashMap<String, Skater>,
+    pub box_trips: HashMap<String, BoxTrip>,
+}
+
+#[derive(Clone, Default)]
+pub struct Skater {
+    pub name: String,
+    pub roster_number: String,
+    pub penalties: BTreeMap<u32, Penalty>,
+}
+
+#[derive(Clone, Default)]
+pub struct Penalty {
+    pub code: String,
+    pub period_number: u8,
+    pub jam_number: u32,
+}
+
+#[derive(Clone, Default)]
+pub struct BoxTrip {
+    pub skater_id: Option<String>,
+    pub start_period_number: Option<u8>,
+    pub start_jam_number: Option<u32>,
+    pub start_between_jams: Option<bool>,
+    pub end_period_number: Option<u8>,
+    pub end_jam_number: Option<u32>,
+    pub walltime_start: Option<u64>,
+    pub walltime_end: Option<u64>,
+}
+
+#[derive(Clone, Default)]
+pub struct Period {
+    pub walltime_start: Option<u64>,
+    pub jams: BTreeMap<u32, Jam>,
+}
+
+#[derive(Clone, Default)]
+pub struct Jam {
+    pub number: Option<u32>,
+    pub team_jams: BTreeMap<u8, TeamJam>,
+}
+
+#[derive(Clone, Default)]
+pub struct TeamJam {
+    pub jam_score: Option<u64>,
+    pub total_score: Option<i64>,
+    pub lead: Option<bool>,
+    pub lost: Option<bool>,
+    pub no_initial: Option<bool>,
+    pub fielding: HashMap<FieldingPosition, Fielding>,
+    pub scoring_trips: BTreeMap<u32, ScoringTrip>,
+}
+
+#[derive(Clone, Default)]
+pub struct Fielding {
+    pub skater_id: Option<String>,
+}
+
+#[derive(Clone, Default)]
+pub struct ScoringTrip {
+    pub score: Option<u64>,
+    pub after_star_pass: Option<bool>,
+    pub duration: Option<u64>,
+    pub jam_clock_end: Option<u64>,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct ValueError {
+    expected: &'static str,
+    value: Value,
+}
+
+#[derive(Clone, Default)]
+pub struct SharedGameModel(Arc<RwLock<GameModel>>);
+
+impl SharedGameModel {
+    pub fn read(&self) -> RwLockReadGuard<'_, GameModel> {
+        self.0.read().unwrap()
+    }
+
+    pub fn snapshot_games(&self, game_ids: &HashSet<String>) -> HashMap<String, Game> {
+        let model = self.read();
+
+        game_ids.iter()
+            .filter_map(|game_id| model.games.get(game_id).map(|game| (game_id.clone(), game.clone())))
+            .collect()
+    }
+
+    pub fn reset(&self) {
+        *self.0.write().unwrap() = GameModel::default();
+    }
+
+    pub fn apply_change(&self, path: &ScoreboardPath, value: &Value) -> Result<Option<String>, ValueError> {
+        self.0.write().unwrap().apply_change(path, value)
+    }
+}
+
+impl fmt::Display for ValueError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Expected {} but received {}", self.expected, self.value)
+    }
+}
+
+impl GameModel {
+    pub fn apply_change(&mut self, path: &ScoreboardPath, value: &Value) -> Result<Option<String>, ValueError> {
+        let game_id = match path {
+            ScoreboardPath::CurrentGame { property } if property == "Game" => {
+                let game_id = as_string(value)?;
+                let previous_game_id = std::mem::replace(&mut self.current_game_id, game_id);
+
+                match self.current_game_id.clone() {
+                    Some(game_id) => {
+                        self.get_game(&game_id);
+                        game_id
+                    },
+                    None => return Ok(previous_game_id),
+                }
+            },
+            ScoreboardPath::PenaltyCode { game_id, code } => {
+                let codes = &mut self.get_game(game_id).penalty_codes;
+                match as_string(value)? {
+                    Some(name) => { codes.insert(code.clone(), name.split(',').next().unwrap_or_default().to_string()); },
+                    None => { codes.remove(code); },
+                }
+                game_id.clone()
+            },
+            ScoreboardPath::Team { game_id, team, property } => {
+                let team = self.get_game(game_id).get_team(*team);
+                match property.as_str() {
+                    "Name" => team.name = as_string(value)?.unwrap_or_default(),
+                    "UniformColor" => team.color = as_string(value)?.unwrap_or_default(),
+                    _ => { }
+                }
+                game_id.clone()
+            },
+            ScoreboardPath::Skater { game_id, team, skater_id, property } => {
+                let skater = self.get_game(game_id).get_team(*team).get_skater(skater_id);
+                match property.as_str() {
+                    "Name" => skater.name = as_string(value)?.unwrap_or_default(),
+                    "RosterNumber" => skater.roster_number = as_string(value)?.unwrap_or_default(),
+                    _ => { }
+                }
+                game_id.clone()
+            },
+            ScoreboardPath::Penalty { game_id, team, skater_id, penalty_id, property } => {
+                let penalty = self.get_game(game_id).get_team(*team).get_skater(skater_id).penalties.entry(*penalty_id).or_default();
+                match property.as_str() {
+                    "Code" => penalty.code = as_string(value)?.unwrap_or_default(),
+                    "PeriodNumber" => penalty.period_number = as_number(value)?.unwrap_or_default(),
+                    "JamNumber" => penalty.jam_number = as_number(value)?.unwrap_or_default(),
+                    _ => { }
+                }
+                game_id.clone()
+            },
+            ScoreboardPath::BoxTrip { game_id, team, box_trip_id, property } => {
+                let box_trip = self.get_game(game_id).get_team(*team).box_trips.entry(box_trip_id.clone()).or_default();
+                match property.as_str() {
+                    "CurrentSkater" => box_trip.skater_id = as_string(value)?,
+                    "StartPeriodNumber" => box_trip.start_period_number = as_number(value)?,
+                    "StartJamNumber" => box_trip.start_jam_number = as_number(value)?,
+                    "StartBetweenJams" => box_trip.start_between_jams = as_bool(value)?,
+                    "EndPeriodNumber" => box_trip.end_period_number = as_number(value)?,
+                    "EndJamNumber" => box_trip.end_jam_number = as_number(value)?,
+                    "WalltimeStart" => box_trip.walltime_start = as_number(value)?,
+                    "WalltimeEnd" => box_trip.walltime_end = as_number(value)?,
+                    _ => { }
+                }
+                game_id.clone()
+            },
+            ScoreboardPath::Period { game_id, period, property } => {
+                let period = self.get_game(game_id).get_period(*period);
+                if property == "WalltimeStart" {
+                    period.walltime_start = as_number(value)?;
+                }
+                game_id.clone()
+            },
+            ScoreboardPath::Jam { game_id, period, jam, property } => {
+                let jam = self.get_game(game_id).get_period(*period).get_jam(*jam);
+                if property == "Number" {
+                    jam.number = as_number(value)?;
+                }
+                game_id.clone()
+            },
+            ScoreboardPath::TeamJam { game_id, period, jam, team, property } => {
+                let team_jam = self.get_game(game_id).get_period(*period).get_jam(*jam).get_team_jam(*team);
+                match property.as_str() {
+                    "JamScore" => team_jam.jam_score = as_number(value)?,
+                    "TotalScore" => team_jam.total_score = as_signed_number(value)?,
+                    "Lead" => team_jam.lead = as_bool(value)?,
+                    "Lost" => team_jam.lost = as_bool(value)?,
+                    "NoInitial" => team_jam.no_initial = as_bool(value)?,
+                    _ => { }
+                }
+                game_id.clone()
+            },
+            ScoreboardPath::Fielding { game_id, period, jam, team, position, property } => {
+                let fielding = self.get_game(game_id).get_period(*period).get_jam(*jam).get_team_jam(*team)
+                    .fielding.entry(*position).or_default();
+                if property == "Skater" {
+                    fielding.skater_id = as_string(value)?;
+                }
+                game_id.clone()
+            },
+            ScoreboardPath::ScoringTrip { game_id, period, jam, team, trip, property } => {
+                let scoring_trip = self.get_game(game_id).get_period(*period).get_jam(*jam).get_team_jam(*team)
+                    .scoring_trips.entry(*trip).or_default();
+                match property.as_str() {
+                    "Score" => scoring_trip.score = as_number(value)?,
+                    "AfterSP" => scoring_trip.after_star_pass = as_bool(value)?,
+                    "Duration" => scoring_trip.duration = as_number(value)?,
+                    "JamClockEnd" => scoring_trip.jam_clock_end = as_number(value)?,
+                    _ => { }
+                }
+                game_id.clone()
+            },
+            _ => return Ok(None),
+        };
+
+        if value.is_null() {
+            self.prune_game(&game_id);
+        }
+
+        Ok(Some(game_id))
+    }
+
+    fn prune_game(&mut self, game_id: &str) {
+        let is_empty = match self.games.get_mut(game_id) {
+            Some(game) => {
+                game.prune();
+                game.is_empty()
+            },
+            None => return,
+        };
+
+        if is_empty {
+            debug!("Removing game {}", game_id);
+            self.games.remove(game_id);
+        }
+    }
+
+    fn get_game(&mut self, game_id: &str) -> &mut Game {
+        self.games.entry(game_id.to_string()).or_insert_with(|| Game {
+            id: game_id.to_string(),
+            ..Default::default()
+        })
+    }
+}
+
+impl Game {
+    fn prune(&mut self) {
+        for team in self.teams.values_mut() {
+            for skater in team.skaters.values_mut() {
+                skater.penalties.retain(|_, penalty| !penalty.is_empty());
+            }
+
+            team.skaters.retain(|_, skater| !skater.is_empty());
+            team.box_trips.retain(|_, box_trip| !box_trip.is_empty());
+        }
+
+        self.teams.retain(|_, team| !team.is_empty());
+
+        for period in self.periods.values_mut() {
+            for jam in period.jams.values_mut() {
+                for team_jam in jam.team_jams.values_mut() {
+                    team_jam.fielding.retain(|_, fielding| !fielding.is_empty());
+                    team_jam.scoring_trips.retain(|_, scoring_trip| !scoring_trip.is_empty());
+                }
+
+                jam.team_jams.retain(|_, team_jam| !team_jam.is_empty());
+            }
+
+            period.jams.retain(|_, jam| !jam.is_empty());
+        }
+
+        self.periods.retain(|_, period| !period.is_empty());
+    }
+
+    fn is_empty(&self) -> bool {
+        self.penalty_codes.is_empty() && self.teams.is_empty() && self.periods.is_empty()
+    }
+
+    fn get_team(&mut self, team: u8) -> &mut Team {
+        self.teams.entry(team).or_default()
+    }
+
+    fn get_period(&mut self, period: u8) -> &mut Period {
+        self.periods.entry(period).or_default()
+    }
+}
+
+impl Team {
+    fn is_empty(&self) -> bool {
+        self.name.is_empty() && self.color.is_empty() && self.skaters.is_empty() && self.box_trips.is_empty()
+    }
+
+    fn get_skater(&mut self, skater_id: &str) -> &mut Skater {
+        self.skaters.entry(skater_id.to_string()).or_default()
+    }
+}
+
+impl Skater {
+    fn is_empty(&self) -> bool {
+        self.name.is_empty() && self.roster_number.is_empty() && self.penalties.is_empty()
+    }
+}
+
+impl Penalty {
+    fn is_empty(&self) -> bool {
+        self.code.is_empty() && self.period_number == 0 && self.jam_number == 0
+    }
+}
+
+impl BoxTrip {
+    fn is_empty(&self) -> bool {
+        self.skater_id.is_none() && self.start_period_number.is_none() && self.start_jam_number.is_none()
+            && self.start_between_jams.is_none() && self.end_period_number.is_none() && self.end_jam_number.is_none()
+            && self.walltime_start.is_none() && self.walltime_end.is_none()
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.walltime_end.unwrap_or(0) > 0
+    }
+
+    pub fn get_duration(&self) -> Option<u64> {
+        match (self.walltime_start, self.walltime_end) {
+            (Some(start), Some(end)) if start > 0 && end >= start => Some(end - start),
+            _ => None,
+        }
+    }
+
+    pub fn spans_multiple_jams(&self) -> bool {
+        if !self.is_complete() {
+            return false;
+        }
+
+        (self.start_period_number, self.start_jam_number) != (self.end_period_number, self.end_jam_number)
+    }
+
+    pub fn covers_jam(&self, period_number: u8, jam_number: u32) -> bool {
+        let start = match (self.start_period_number, self.start_jam_number) {
+            (Some(period), Some(jam)) if period > 0 => (period, jam),
+            _ => return false,
+        };
+
+        // Trips that start between jams are recorded against the previous jam
+        let jam = (period_number, jam_number);
+        let has_started = jam > start || (jam == start && !self.start_between_jams.unwrap_or(false));
+
+        let has_ended = match (self.end_period_number, self.end_jam_number) {
+            (Some(period), Some(end_jam)) if period > 0 => jam > (period, end_jam),
+            _ => false,
+        };
+
+        has_started && !has_ended
+    }
+}
+
+impl Period {
+    fn is_empty(&self) -> bool {
+        self.walltime_start.is_none() && self.jams.is_empty()
+    }
+
+    fn get_jam(&mut self, jam: u32) -> &mut Jam {
+        self.jams.entry(jam).or_default()
+    }
+}
+
+impl Jam {
+    fn is_empty(&self) -> bool {
+        self.number.is_none() && self.team_jams.is_empty()
+    }
+
+    fn get_team_jam(&mut self, team: u8) -> &mut TeamJam {
+        self.team_jams.entry(team).or_default()
+    }
+}
+
+impl TeamJam {
+    fn is_empty(&self) -> bool {
+        self.jam_score.is_none() && self.total_score.is_none() && self.lead.is_none() && self.lost.is_none()
+            && self.no_initial.is_none() && self.fielding.is_empty() && self.scoring_trips.is_empty()
+    }
+
+    pub fn get_skater_id(&self, position: FieldingPosition) -> Option<&String> {
+        self.fielding.get(&position).and_then(|f| f.skater_id.as_ref())
+    }
+
+    pub fn has_lead(&self) -> bool {
+        self.lead.unwrap_or(false)
+    }
+
+    pub fn has_lost(&self) -> bool {
+        self.lost.unwrap_or(false)
+    }
+
+    pub fn has_no_initial(&self) -> bool {
+        self.no_initial.unwrap_or(false)
+    }
+
+    pub fn get_jammer_id(&self, after_star_pass: bool) -> Option<&String> {
+        if after_star_pass {
+            self.get_skater_id(FieldingPosition::Pivot)
+        } else {
+            self.get_skater_id(FieldingPosition::Jammer)
+        }
+    }
+
+    pub fn get_time_to_initial(&self) -> Option<u64> {
+        if self.has_no_initial() {
+            return None;
+        }
+
+        self.scoring_trips.get(&1)
+            .and_then(|trip| trip.jam_clock_end)
+            .filter(|jam_clock_end| *jam_clock_end > 0)
+    }
+}
+
+impl Fielding {
+    fn is_empty(&self) -> bool {
+        self.skater_id.is_none()
+    }
+}
+
+impl ScoringTrip {
+    fn is_empty(&self) -> bool {
+        self.score.is_none() && self.after_star_pass.is_none() && self.duration.is_none() && self.jam_clock_end.is_none()
+    }
+}
+
+fn as_string(value: &Value) -> Result<Option<String>, ValueError> {
+    match value {
+        Value::Null => Ok(None),
+        Value::String(s) => Ok(Some(s.clone())),
+        _ => Err(ValueError { expected: "a string", value: value.clone() }),
+    }
+}
+
+fn as_bool(value: &Value) -> Result<Option<bool>, ValueError> {
+    match value {
+        Value::Null => Ok(None),
+        Value::Bool(b) => Ok(Some(*b)),
+        _ => Err(ValueError { expected: "a boolean", value: value.clone() }),
+    }
+}
+
+fn as_number<T: TryFrom<u64>>(value: &Value) -> Result<Option<T>, ValueError> {
+    if value.is_null() {
+        return Ok(None);
+    }
+
+    value.as_u64()
+        .and_then(|n| T::try_from(n).ok())
+        .map(Some)
+        .ok_or(ValueError { expected: "a non-negative integer", value: value.clone() })
+}
+
+fn as_signed_number(value: &Value) -> Result<Option<i64>, ValueError> {
+    if value.is_null() {
+        return Ok(None);
+    }
+
+    value.as_i64()
+        .map(Some)
+        .ok_or(ValueError { expected: "an integer", value: value.clone() })
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn apply(model: &mut GameModel, key: &str, value: Value) -> Result<Option<String>, ValueError> {
+        model.apply_change(&ScoreboardPath::parse(key).unwrap(), &value)
+    }
+
+    fn get_team_jam<'a>(model: &'a GameModel, game_id: &str, period: u8, jam: u32, team: u8) -> Option<&'a TeamJam> {
+        model.games.get(game_id)?.periods.get(&period)?.jams.get(&jam)?.team_jams.get(&team)
+    }
+
+    #[test]
+    fn applies_values_to_games() {
+        let mut model = GameModel::default();
+
+        assert_eq!(apply(&mut model, "ScoreBoard.Game(g1).Team(1).Name", json!("Home")), Ok(Some("g1".to_string())));
+        assert_eq!(apply(&mut model, "ScoreBoard.Game(g1).Period(1).Jam(2).TeamJam(1).JamScore", json!(4)), Ok(Some("g1".to_string())));
+        assert_eq!(apply(&mut model, "ScoreBoard.Game(g1).Period(1).Jam(2).TeamJam(1).Fielding(Jammer).Skater", json!("s1")), Ok(Some("g1".to_string())));
+
+        assert_eq!(model.games["g1"].teams[&1].name, "Home");
+
+        let team_jam = get_team_jam(&model, "g1", 1, 2, 1).unwrap();
+        assert_eq!(team_jam.jam_score, Some(4));
+        assert_eq!(team_jam.get_skater_id(FieldingPosition::Jammer), Some(&"s1".to_string()));
+    }
+
+    #[test]
+    fn ignores_unknown_paths() {
+        let mut model = GameModel::default();
+
+        assert_eq!(model.apply_change(&ScoreboardPath::Other, &json!("value")), Ok(None));
+        assert_eq!(apply(&mut model, "ScoreBoard.Game(g1).Team(1).Unknown", json!("value")), Ok(Some("g1".to_string())));
+        assert!(model.games["g1"].teams[&1].is_empty());
+    }
+
+    #[test]
+    fn null_values_clear_properties() {
+        let mut model = GameModel::default();
+
+        apply(&mut model, "ScoreBoard.Game(g1).Team(1).Name", json!("Home")).unwrap();
+        apply(&mut model, "ScoreBoard.Game(g1).Period(1).Jam(1).TeamJam(1).JamScore", json!(4)).unwrap();
+        apply(&mut model, "ScoreBoard.Game(g1).Period(1).Jam(1).TeamJam(1).Lead", json!(true)).unwrap();
+
+        apply(&mut model, "ScoreBoard.Game(g1).Period(1).Jam(1).TeamJam(1).JamScore", Value::Null).unwrap();
+
+        let team_jam = get_team_jam(&model, "g1", 1, 1, 1).unwrap();
+        assert_eq!(team_jam.jam_score, None);
+        assert_eq!(team_jam.lead, Some(true));
+    }
+
+    #[test]
+    fn null_values_prune_empty_entries() {
+        let mut model = GameModel::default();
+
+        apply(&mut model, "ScoreBoard.Game(g1).Team(1).Name", json!("Home")).unwrap();
+        apply(&mut model, "ScoreBoard.Game(g1).Team(1).Skater(s1).Penalty(1).Code", json!("B")).unwrap();
+        apply(&mut model, "ScoreBoard.Game(g1).Period(1).Jam(1).TeamJam(1).Fielding(Jammer).Skater", json!("s1")).unwrap();
+
+        apply(&mut model, "ScoreBoard.Game(g1).Period(1).Jam(1).TeamJam(1).Fielding(Jammer).Skater", Value::Null).unwrap();
+        apply(&mut model, "ScoreBoard.Game(g1).Team(1).Skater(s1).Penalty(1).Code", Value::Null).unwrap();
+
+        let game = &model.games["g1"];
+        assert!(game.periods.is_empty());
+        assert!(game.teams[&1].skaters.is_empty());
+        assert_eq!(game.teams[&1].name, "Home");
+    }
+
+    #[test]
+    fn prune_game_removes_empty_games() {
+        let mut model = GameModel::default();
+
+        apply(&mut model, "ScoreBoard.Game(g1).Team(1).Name", json!("Home")).unwrap();
+        apply(&mut model, "ScoreBoard.Game(g2).PenaltyCode(B)", json!("Back Block")).unwrap();
+
+        assert_eq!(apply(&mut model, "ScoreBoard.Game(g1).Team(1).Name", Value::Null), Ok(Some("g1".to_string())));
+        assert_eq!(apply(&mut model, "ScoreBoard.Game(g2).PenaltyCode(B)", Value::Null), Ok(Some("g2".to_string())));
+
+        assert!(model.games.is_empty());
+    }
+
+    #[test]
+    fn bad_value_types_are_rejected() {
+        let mut model = GameModel::default();
+
+        apply(&mut model, "ScoreBoard.Game(g1).Team(1).Name", json!("Home")).unwrap();
+        apply(&mut model, "ScoreBoard.Game(g1).Period(1).Jam(1).TeamJam(1).JamScore", json!(4)).unwrap();
+        apply(&mut model, "ScoreBoard.Game(g1).Period(1).Jam(1).TeamJam(1).Lead", json!(true)).unwrap();
+
+        assert!(apply(&mut model, "ScoreBoard.Game(g1).Team(1).Name", json!(12)).is_err());
+        assert!(apply(&mut model, "ScoreBoard.Game(g1).Period(1).Jam(1).TeamJam(1).JamScore", json!("four")).is_err());
+        assert!(apply(&mut model, "ScoreBoard.Game(g1).Period(1).Jam(1).TeamJam(1).JamScore", json!(-4)).is_err());
+        assert!(apply(&mut model, "ScoreBoard.Game(g1).Period(1).Jam(1).TeamJam(1).Lead", json!("true")).is_err());
+
+        assert_eq!(model.games["g1"].teams[&1].name, "Home");
+
+        let team_jam = get_team_jam(&model, "g1", 1, 1, 1).unwrap();
+        assert_eq!(team_jam.jam_score, Some(4));
+        assert_eq!(team_jam.lead, Some(true));
+    }
+
+    #[test]
+    fn bad_current_game_keeps_current_game() {
+        let mut model = GameModel::default();
+
+        assert_eq!(apply(&mut model, "ScoreBoard.CurrentGame.Game", json!("g1")), Ok(Some("g1".to_string())));
+        assert!(apply(&mut model, "ScoreBoard.CurrentGame.Game", json!(1)).is_err());
+        assert_eq!(model.current_game_id, Some("g1".to_string()));
+
+        assert_eq!(apply(&mut model, "ScoreBoard.CurrentGame.Game", Value::Null), Ok(Some("g1".to_string())));
+        assert_eq!(model.current_game_id, None);
+    }
+}
@@ -0,0 +1,211 @@
+use std::{collections::HashMap, marker::PhantomData, sync::Arc};
+
+use tokio::sync::Mutex;
+
+use log::{debug, error};
+use serde_json::Value;
+
+use crate::{socket_server::{UpdateProvider, Update, SocketServer}, scoreboard_connector::ScoreboardStateChange, scoreboard_source::ScoreboardSource, game_model::Game};
+
+pub trait GameStats {
+    const DATA_TYPE: &'static str;
+    const TOPICS: &'static [&'static str];
+
+    fn compute_game(game: &Game) -> Value;
+    fn get_empty_state() -> Value;
+}
+
+pub struct GameStatsProvider<T: GameStats> {
+    game_states: HashMap<String, Value>,
+    stats: PhantomData<fn() -> T>,
+}
+
+impl<T: GameStats + 'static> GameStatsProvider<T> {
+    pub async fn register(scoreboard: &mut dyn ScoreboardSource, socket_server: &mut SocketServer) {
+        let provider = Arc::new(Mutex::new(GameStatsProvider::<T> {
+            game_states: HashMap::new(),
+            stats: PhantomData,
+        }));
+
+        let mut receiver = scoreboard.get_receiver();
+
+        let update_sender = socket_server.get_update_sender();
+        socket_server.register_update_provider(T::DATA_TYPE, provider.clone()).await;
+
+        tokio::task::spawn(async move {
+            while let Some(state_update) = receiver.recv().await {
+                let updates: Vec<Update> = {
+                    let mut locked_provider = provider.lock().await;
+
+                    let game_updates = locked_provider.process_state_update(state_update);
+
+                    debug!("{} games updated", game_updates.len());
+                    game_updates.into_iter()
+                        .map(|(game_id, update)| Update {
+                            game_id,
+                            data_type: T::DATA_TYPE.to_string(),
+                            update,
+                        })
+                        .collect()
+                };
+
+                for update in updates {
+                    debug!("Sending {} update for game {}", T::DATA_TYPE, update.game_id);
+                    if let Err(e) = update_sender.send(update).await {
+                        error!("Error sending update on mpsc: {:?}", e);
+                    }
+                }
+            }
+        });
+
+        for topic in T::TOPICS {
+            scoreboard.register_topic(topic);
+        }
+    }
+
+    fn process_state_update(&mut self, change: ScoreboardStateChange) -> Vec<(String, Value)> {
+        debug!("Processing stats update for {}", T::DATA_TYPE);
+
+        let reset_game_ids: Vec<String> = if change.is_reset {
+            self.game_states.drain().map(|(game_id, _)| game_id).collect()
+        } else {
+            Vec::new()
+        };
+
+        let games = change.model.snapshot_games(&change.updated_game_ids);
+
+        let mut updates: Vec<(String, Value)> = change.updated_game_ids.iter()
+            .filter_map(|game_id| {
+                let game = match games.get(game_id) {
+                    Some(g) => g,
+                    None => return self.game_states.remove(game_id)
+                        .map(|_| (game_id.clone(), T::get_empty_state())),
+                };
+
+                let state = T::compute_game(game);
+
+                match self.game_states.get(game_id) {
+                    Some(previous_state) if *previous_state == state => return None,
+                    None if state == T::get_empty_state() => return None,
+                    _ => { }
+                }
+
+                self.game_states.insert(game_id.clone(), state.clone());
+
+                Some((game_id.clone(), state))
+            })
+            .collect();
+
+        let cleared_updates: Vec<(String, Value)> = reset_game_ids.into_iter()
+            .filter(|game_id| !self.game_states.contains_key(game_id) && !updates.iter().any(|(id, _)| id == game_id))
+            .map(|game_id| (game_id, T::get_empty_state()))
+            .collect();
+
+        updates.extend(cleared_updates);
+
+        updates
+    }
+}
+
+impl<T: GameStats> UpdateProvider for GameStatsProvider<T> {
+    fn get_state(&self, game_id: &str) -> Option<Value> {
+        self.game_states.get(game_id).cloned()
+    }
+
+    fn get_empty_state(&self) -> Value {
+        T::get_empty_state()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::scoreboard_connector::{ScoreboardStateStore, ScoreboardStateUpdate};
+
+    struct TeamNames;
+
+    impl GameStats for TeamNames {
+        const DATA_TYPE: &'static str = "TeamNames";
+        const TOPICS: &'static [&'static str] = &["ScoreBoard.Game(*).Team(*).Name"];
+
+        fn compute_game(game: &Game) -> Value {
+            json!(game.teams.values().map(|team| team.name.clone()).collect::<Vec<_>>())
+        }
+
+        fn get_empty_state() -> Value {
+            json!([])
+        }
+    }
+
+    fn apply(store: &mut ScoreboardStateStore, key: &str, value: Value) -> ScoreboardStateChange {
+        store.apply_update(ScoreboardStateUpdate { state: [(key.to_string(), value)].into() });
+        store.take_changes()
+    }
+
+    fn new_provider() -> GameStatsProvider<TeamNames> {
+        GameStatsProvider { game_states: HashMap::new(), stats: PhantomData }
+    }
+
+    #[test]
+    fn reports_only_changed_games() {
+        let mut store = ScoreboardStateStore::new();
+        let mut provider = new_provider();
+
+        let change = apply(&mut store, "ScoreBoard.Game(g1).Team(1).Name", json!("Home"));
+        assert_eq!(provider.process_state_update(change), vec![("g1".to_string(), json!(["Home"]))]);
+        assert_eq!(provider.get_state("g1"), Some(json!(["Home"])));
+
+        let change = apply(&mut store, "ScoreBoard.Game(g1).Team(1).Name", json!("Home"));
+        assert!(provider.process_state_update(change).is_empty());
+    }
+
+    #[test]
+    fn skips_games_without_stats() {
+        let mut store = ScoreboardStateStore::new();
+        let mut provider = new_provider();
+
+        let change = apply(&mut store, "ScoreBoard.Game(g1).PenaltyCode(A)", json!("Back Block"));
+        assert!(provider.process_state_update(change).is_empty());
+        assert_eq!(provider.get_state("g1"), None);
+        assert_eq!(provider.get_empty_state(), json!([]));
+    }
+
+    #[test]
+    fn sends_empty_state_for_removed_games() {
+        let mut store = ScoreboardStateStore::new();
+        let mut provider = new_provider();
+
+        provider.process_state_update(apply(&mut store, "ScoreBoard.Game(g1).Team(1).Name", json!("Home")));
+        let change = apply(&mut store, "ScoreBoard.Game(g1).Team(1).Name", Value::Null);
+
+        assert_eq!(provider.process_state_update(change), vec![("g1".to_string(), json!([]))]);
+        assert_eq!(provider.get_state("g1"), None);
+
+        let change = apply(&mut store, "ScoreBoard.Game(g1).Team(1).Name", Value::Null);
+        assert!(provider.process_state_update(change).is_empty());
+    }
+
+    #[test]
+    fn reset_sends_empty_state_for_previous_games() {
+        let mut store = ScoreboardStateStore::new();
+        let mut provider = new_provider();
+
+        provider.process_state_update(apply(&mut store, "ScoreBoard.Game(g1).Team(1).Name", json!("Home")));
+        provider.process_state_update(apply(&mut store, "ScoreBoard.Game(g2).Team(1).Name", json!("Away")));
+
+        store.reset();
+        let change = apply(&mut store, "ScoreBoard.Game(g2).Team(1).Name", json!("Away"));
+
+        let mut updates = provider.process_state_update(change);
+        updates.sort_by(|a, b| a.0.cmp(&b.0));
+
+        assert_eq!(updates, vec![
+            ("g1".to_string(), json!([])),
+            ("g2".to_string(), json!(["Away"])),
+        ]);
+        assert_eq!(provider.get_state("g1"), None);
+        assert_eq!(provider.get_state("g2"), Some(json!(["Away"])));
+    }
+}
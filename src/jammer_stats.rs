@@ -1,48 +1,9 @@
-use std::{collections::HashMap, sync::Arc};
-
-use tokio::sync::Mutex;
-
-use log::{debug, error};
-use regex::Regex;
 use serde::{Serialize, Deserialize};
 use serde_json::{json, Value};
 
-use crate::{socket_server::{UpdateProvider, Update, SocketServer}, scoreboard_connector::{ScoreboardConnection, ScoreboardState}};
-
-#[derive(Clone)]
-struct JammerScoreMatches {
-    game: String,
-    jam: u32,
-    period: u8,
-    team: u8,
-    score: u64,
-}
-
-#[derive(Clone)]
-struct JammerSkaterMatches {
-    game: String,
-    jam: u32,
-    period: u8,
-    team: u8,
-    skater_id: String,
-}
-
-#[derive(Clone)]
-struct SkaterNameMatches {
-    game: String,
-    team: u8,
-    skater_id: String,
-    name: String
-}
-
-#[derive(Clone)]
-enum Match { 
-    JammerScore(JammerScoreMatches),
-    JammerSkater(JammerSkaterMatches),
-    SkaterName(SkaterNameMatches),
-}
+use crate::{game_stats::GameStats, scoreboard_path::FieldingPosition, game_model::{Game, TeamJam}};
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, PartialEq)]
 struct JammerInfo {
     #[serde(rename = "name")]
     name: String,
@@ -60,229 +21,309 @@ struct JammerInfo {
     net_score: i64,
 
     #[serde(rename = "leadCount")]
-    lead_count: u32,
+    lead_count: usize,
+
+    #[serde(rename = "leadPercentage")]
+    lead_percentage: f32,
+
+    #[serde(rename = "lostCount")]
+    lost_count: usize,
+
+    #[serde(flatten)]
+    initial_pass_times: InitialPassTimes,
+}
+
+#[derive(Serialize, Deserialize, PartialEq)]
+struct TeamInfo {
+    #[serde(rename = "team")]
+    team: u8,
 
+    #[serde(flatten)]
+    initial_pass_times: InitialPassTimes,
+}
+
+#[derive(Serialize, Deserialize, PartialEq)]
+struct InitialPassTimes {
     #[serde(rename = "meanTimeToInitial")]
-    mean_time_to_initial: f32,
+    mean_time_to_initial: Option<f32>,
+
+    #[serde(rename = "bestTimeToInitial")]
+    best_time_to_initial: Option<f32>,
+
+    #[serde(rename = "worstTimeToInitial")]
+    worst_time_to_initial: Option<f32>,
+
+    #[serde(rename = "noInitialCount")]
+    no_initial_count: usize,
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, PartialEq, Default)]
 struct JammerStatsStates {
     #[serde(rename = "jammers")]
     pub jammers: Vec<JammerInfo>,
-}
 
-pub struct JammerStats {
-    game_states: HashMap<String, JammerStatsStates>,
-    jam_score_regex: Regex,
-    jam_skater_regex: Regex,
-    skater_name_regex: Regex,
+    #[serde(rename = "teams")]
+    pub teams: Vec<TeamInfo>,
 }
 
-impl JammerStats {
-    pub async fn new(scoreboard: &mut ScoreboardConnection, socket_server: &mut SocketServer) {
-        let jammer_stats = Arc::new(Mutex::new(JammerStats { 
-            game_states: HashMap::new(),
-            jam_score_regex: Regex::new(r#"^ScoreBoard\.Game\(([^\)]+)\)\.Period\((\d+)\)\.Jam\((\d+)\)\.TeamJam\((\d+)\)\.JamScore$"#).unwrap(),
-            jam_skater_regex: Regex::new(r#"^ScoreBoard\.Game\(([^\)]+)\)\.Period\((\d+)\)\.Jam\((\d+)\)\.TeamJam\((\d+)\)\.Fielding\(Jammer\)\.Skater$"#).unwrap(),
-            skater_name_regex: Regex::new(r#"^ScoreBoard\.Game\(([^\)]+)\)\.Team\((\d+)\)\.Skater\(([^\)]+)\)\.Name$"#).unwrap(),
-        }));
-
-        let mut receiver = scoreboard.get_receiver();
-
-        let update_sender = socket_server.get_update_sender();
-        socket_server.register_update_provider(&"JammerStats".to_string(), jammer_stats.clone()).await;
-
-        tokio::task::spawn(async move {
-            while let Ok(state_update) = receiver.recv().await {
-                let mut locked_jammer_stats = jammer_stats.lock().await;
-
-                let update_game_ids = locked_jammer_stats.process_state_update(state_update);
-
-                debug!("{} games updated", update_game_ids.len());
-                for update_game_id in update_game_ids {
-                    let update = if let Some(s) = locked_jammer_stats.game_states.get(&update_game_id) {
-                        s.clone()
-                    } else {
-                        continue;
-                    };
-
-                    debug!("Sending JammerStats update for game {}", update_game_id.clone());
-                    if let Err(e) = update_sender.send(Update { game_id: update_game_id, data_type: "JammerStats".to_string(), update: json!(update.clone())}) {
-                        error!("Error sending update on mpsc: {:?}", e);
-                    }
-                }
+impl InitialPassTimes {
+    fn from_team_jams<'a>(team_jams: impl Iterator<Item = &'a TeamJam>) -> InitialPassTimes {
+        let (times, no_initial_count) = team_jams.fold((Vec::new(), 0), |(mut times, no_initial_count), team_jam| {
+            if team_jam.has_no_initial() {
+                return (times, no_initial_count + 1);
+            }
+
+            if let Some(time) = team_jam.get_time_to_initial() {
+                times.push(time as f32 / 1000.0);
             }
+
+            (times, no_initial_count)
         });
 
-        scoreboard.register_topic("ScoreBoard.Game(*).Team(*).Skater(*).Name");
-        scoreboard.register_topic("ScoreBoard.Game(*).Period(*).Jam(*).TeamJam(*).Fielding(Jammer).Skater");
-        scoreboard.register_topic("ScoreBoard.Game(*).Period(*).Jam(*).TeamJam(*).JamScore");
+        let mean_time_to_initial = if times.is_empty() {
+            None
+        } else {
+            Some(times.iter().sum::<f32>() / times.len() as f32)
+        };
+
+        InitialPassTimes {
+            mean_time_to_initial,
+            best_time_to_initial: times.iter().copied().reduce(f32::min),
+            worst_time_to_initial: times.iter().copied().reduce(f32::max),
+            no_initial_count,
+        }
     }
+}
 
-    fn process_state_update(&mut self, update: ScoreboardState) -> Vec<String> {
-        debug!("Processing stats update for jammer stats");
+pub struct JammerStats;
 
-        let stats_by_game = update.iter()
-            .filter_map(|s| self.get_relevant_states(s))
-            .fold(HashMap::new(), |mut map, match_info| {
-                let game_id = match match_info.clone() {
-                    Match::JammerScore(jammer_score) => jammer_score.game,
-                    Match::JammerSkater(jammer_skater) => jammer_skater.game,
-                    Match::SkaterName(skater_name) => skater_name.game,
+impl JammerStats {
+    fn get_game_stats(game: &Game) -> JammerStatsStates {
+        let team_jams: Vec<(u8, &TeamJam, Option<&TeamJam>)> = game.periods.values()
+            .flat_map(|period| period.jams.values())
+            .flat_map(|jam| jam.team_jams.iter()
+                .map(|(team, team_jam)| (*team, team_jam, jam.team_jams.iter()
+                    .find(|(opponent, _)| **opponent != *team)
+                    .map(|(_, opponent_jam)| opponent_jam))))
+            .collect();
+
+        let mut jammer_stats: Vec<JammerInfo> = game.teams.iter()
+            .flat_map(|(team, team_details)| team_details.skaters.iter().map(|(skater_id, skater)| (*team, skater_id, skater)))
+            .filter(|(_, _, skater)| !skater.name.is_empty())
+            .map(|(team, skater_id, skater)| {
+                let jammer_jams: Vec<(&TeamJam, Option<&TeamJam>)> = team_jams.iter()
+                    .filter(|(jam_team, team_jam, _)| *jam_team == team && team_jam.get_skater_id(FieldingPosition::Jammer) == Some(skater_id))
+                    .map(|(_, team_jam, opponent_jam)| (*team_jam, *opponent_jam))
+                    .collect();
+
+                let skater_jams: Vec<&TeamJam> = jammer_jams.iter()
+                    .map(|(team_jam, _)| *team_jam)
+                    .collect();
+
+                let jammer_score = skater_jams.iter()
+                    .fold(0, |score, team_jam| score + team_jam.jam_score.unwrap_or(0));
+
+                let opponent_score = jammer_jams.iter()
+                    .fold(0, |score, (_, opponent_jam)| score + opponent_jam.and_then(|j| j.jam_score).unwrap_or(0));
+
+                let lead_count = skater_jams.iter()
+                    .filter(|team_jam| team_jam.has_lead())
+                    .count();
+
+                let lost_count = skater_jams.iter()
+                    .filter(|team_jam| team_jam.has_lost())
+                    .count();
+
+                let lead_percentage = if skater_jams.is_empty() {
+                    0.0
+                } else {
+                    lead_count as f32 * 100.0 / skater_jams.len() as f32
                 };
-        
-                if !map.contains_key(&game_id) {
-                    map.insert(game_id.clone(), Vec::new());
+
+                JammerInfo {
+                    name: skater.name.clone(),
+                    team,
+                    jam_count: skater_jams.len(),
+                    total_score: jammer_score,
+                    net_score: jammer_score as i64 - opponent_score as i64,
+                    lead_count,
+                    lead_percentage,
+                    lost_count,
+                    initial_pass_times: InitialPassTimes::from_team_jams(skater_jams.iter().copied()),
                 }
-        
-                map.get_mut(&game_id).unwrap().push(Box::new(match_info));
-        
-                map
-            });
-        
-        for game_id in stats_by_game.keys() {
-            let game_stats = stats_by_game.get(game_id).unwrap();
-
-            let skater_names: Vec<SkaterNameMatches> = game_stats.iter()
-                .filter_map(|m| {
-                    if let Match::SkaterName(s) = *m.clone() { 
-                        Some(s) 
-                    } else { 
-                        None 
-                    } 
-                })
-                .collect();
-
-            debug!("Found {} skaters for game {}", skater_names.len(), game_id);
-
-            struct JamInfo {
-                jammer_id: String,
-                score: u64,
-            }
+            })
+            .filter(|stats| stats.jam_count > 0)
+            .collect();
 
-            let jam_stats = game_stats.iter()
-                .filter_map(|m| {
-                    let m = *m.clone();
-                    if let Match::JammerScore(_) = m {
-                        Some(m)
-                    } else if let Match::JammerSkater(_) = m {
-                        Some(m)
-                    } else {
-                        None
-                    }
-                })
-                .fold(HashMap::new(), |mut map, m| {
-                    let key = match m.clone() {
-                        Match::JammerScore(jammer_score) => (jammer_score.period, jammer_score.jam, jammer_score.team),
-                        Match::JammerSkater(skater) => (skater.period, skater.jam, skater.team),
-                        _ => panic!("Unexpected state type")
-                    };
-
-                    if !map.contains_key(&key) {
-                        map.insert(key.clone(), JamInfo { jammer_id: "".to_string(), score: 0 });
-                    }
-                    let jam = map.get_mut(&key).unwrap();
-
-                    match m.clone() {
-                        Match::JammerScore(score) => jam.score = score.score,
-                        Match::JammerSkater(skater) => jam.jammer_id = skater.skater_id.clone(),
-                        _ => panic!("Unexpected state type")
-                    };
-                    
-                    map
-                });
-
-            let jammer_stats: Vec<JammerInfo> = skater_names.iter()
-                .map(|skater| {
-                    let skater_jam_stats: Vec<(&u8, &u32, &u8, &JamInfo)> = jam_stats.iter()
-                        .filter_map(|((period_number, jam_number, team), jam)| {
-                            if jam.jammer_id.eq(&skater.skater_id) {
-                                Some((period_number, jam_number, team, jam))
-                            } else {
-                                None
-                            }
-                        })
-                        .collect();
-
-                    let jammer_score = skater_jam_stats.iter()
-                        .filter(|(_, _, team, _)| skater.team == **team)
-                        .fold(0, |score, (_, _, _, jam)| score + jam.score);
-
-                    let jammer_net_score = skater_jam_stats.iter()
-                        .fold(0, |score, (_, _, team, jam)| {
-                            if skater.team == **team {
-                                score + jam.score as i64
-                            } else {
-                                score - jam.score as i64
-                            }
-                        });
-                    
-                    JammerInfo {
-                        name: skater.name.clone(),
-                        team: skater.team,
-                        jam_count: skater_jam_stats.len(),
-                        total_score: jammer_score,
-                        net_score: jammer_net_score,
-                        lead_count: 0, /* TODO */
-                        mean_time_to_initial: 0.0, /* TODO */
-                    }
-                })
-                .filter(|stats| stats.jam_count > 0)
-                .collect();
-
-            self.game_states.insert(game_id.clone(), JammerStatsStates { jammers: jammer_stats });
-        }
+        jammer_stats.sort_by(|a, b| a.team.cmp(&b.team).then_with(|| a.name.cmp(&b.name)));
+
+        let teams = game.teams.keys()
+            .map(|team| TeamInfo {
+                team: *team,
+                initial_pass_times: InitialPassTimes::from_team_jams(team_jams.iter()
+                    .filter(|(jam_team, _, _)| jam_team == team)
+                    .map(|(_, team_jam, _)| *team_jam)),
+            })
+            .collect();
 
-        stats_by_game.keys().map(|k| k.clone()).collect()
+        JammerStatsStates { jammers: jammer_stats, teams }
     }
+}
 
-    fn get_relevant_states(&self, (key, value): (&String, &Value)) -> Option<Match> {
-        if self.jam_score_regex.is_match(key) {
-            self.jam_score_regex.captures(key).map(|c| {
-                let (_, [game, period, jam, team]) = c.extract();
-                Match::JammerScore(
-                    JammerScoreMatches {
-                        game: game.to_string(),
-                        jam: jam.parse::<u32>().unwrap(),
-                        period: period.parse::<u8>().unwrap(),
-                        team: team.parse::<u8>().unwrap(),
-                        score: value.as_u64().unwrap()
-                    })
-            })                    
-        } else if self.jam_skater_regex.is_match(key) {
-            self.jam_skater_regex.captures(key).map(|c| {
-                let (_, [game, period, jam, team]) = c.extract();
-
-                Match::JammerSkater(
-                    JammerSkaterMatches {
-                        game: game.to_string(),
-                        jam: jam.parse::<u32>().unwrap(),
-                        period: period.parse::<u8>().unwrap(),
-                        team: team.parse::<u8>().unwrap(),
-                        skater_id: value.as_str().unwrap().to_string(),
-                    })
-            })
-        } else if self.skater_name_regex.is_match(key) {
-            self.skater_name_regex.captures(key).map(|c| {
-                let (_, [game, team, skater_id]) = c.extract();
-
-                Match::SkaterName(
-                    SkaterNameMatches {
-                        game: game.to_string(),
-                        team: team.parse::<u8>().unwrap(),
-                        skater_id: skater_id.to_string(),
-                        name: value.as_str().unwrap().to_string(),
-                    })
-            })
-        } else {
-            None
-        }
+impl GameStats for JammerStats {
+    const DATA_TYPE: &'static str = "JammerStats";
+    const TOPICS: &'static [&'static str] = &[
+        "ScoreBoard.Game(*).Team(*).Skater(*).Name",
+        "ScoreBoard.Game(*).Period(*).Jam(*).TeamJam(*).Fielding(Jammer).Skater",
+        "ScoreBoard.Game(*).Period(*).Jam(*).TeamJam(*).JamScore",
+        "ScoreBoard.Game(*).Period(*).Jam(*).TeamJam(*).Lead",
+        "ScoreBoard.Game(*).Period(*).Jam(*).TeamJam(*).Lost",
+        "ScoreBoard.Game(*).Period(*).Jam(*).TeamJam(*).NoInitial",
+        "ScoreBoard.Game(*).Period(*).Jam(*).TeamJam(*).ScoringTrip(*).JamClockEnd",
+    ];
+
+    fn compute_game(game: &Game) -> Value {
+        json!(Self::get_game_stats(game))
+    }
+
+    fn get_empty_state() -> Value {
+        json!(JammerStatsStates::default())
     }
 }
 
-impl UpdateProvider for JammerStats {
-    fn get_state(&self, game_id: &String) -> serde_json::Value {
-        json!(self.game_states.get(game_id).unwrap().clone())
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use serde_json::json;
+
+    use super::*;
+    use crate::scoreboard_connector::{ScoreboardStateStore, ScoreboardStateUpdate};
+
+    fn get_game(entries: &[(&str, Value)]) -> Game {
+        let mut store = ScoreboardStateStore::new();
+        store.apply_update(ScoreboardStateUpdate {
+            state: entries.iter().map(|(key, value)| (key.to_string(), value.clone())).collect(),
+        });
+
+        let change = store.take_changes();
+        change.model.snapshot_games(&HashSet::from(["g1".to_string()])).remove("g1").unwrap()
+    }
+
+    fn get_jam_entries() -> Vec<(&'static str, Value)> {
+        vec![
+            ("ScoreBoard.Game(g1).Team(1).Skater(h1).Name", json!("Home Jammer")),
+            ("ScoreBoard.Game(g1).Team(1).Skater(h2).Name", json!("Home Reserve")),
+            ("ScoreBoard.Game(g1).Team(2).Skater(a1).Name", json!("Away Jammer")),
+            ("ScoreBoard.Game(g1).Period(1).Jam(1).TeamJam(1).Fielding(Jammer).Skater", json!("h1")),
+            ("ScoreBoard.Game(g1).Period(1).Jam(1).TeamJam(2).Fielding(Jammer).Skater", json!("a1")),
+            ("ScoreBoard.Game(g1).Period(1).Jam(1).TeamJam(1).JamScore", json!(12)),
+            ("ScoreBoard.Game(g1).Period(1).Jam(1).TeamJam(2).JamScore", json!(4)),
+            ("ScoreBoard.Game(g1).Period(1).Jam(2).TeamJam(1).Fielding(Jammer).Skater", json!("h1")),
+            ("ScoreBoard.Game(g1).Period(1).Jam(2).TeamJam(2).Fielding(Jammer).Skater", json!("a1")),
+            ("ScoreBoard.Game(g1).Period(1).Jam(2).TeamJam(1).JamScore", json!(0)),
+            ("ScoreBoard.Game(g1).Period(1).Jam(2).TeamJam(2).JamScore", json!(9)),
+        ]
+    }
+
+    fn get_jammer<'a>(stats: &'a JammerStatsStates, name: &str) -> &'a JammerInfo {
+        stats.jammers.iter().find(|jammer| jammer.name == name).unwrap()
+    }
+
+    #[test]
+    fn net_score_subtracts_opponent_jam_scores() {
+        let stats = JammerStats::get_game_stats(&get_game(&get_jam_entries()));
+
+        let home_jammer = get_jammer(&stats, "Home Jammer");
+        assert_eq!(home_jammer.jam_count, 2);
+        assert_eq!(home_jammer.total_score, 12);
+        assert_eq!(home_jammer.net_score, -1);
+
+        let away_jammer = get_jammer(&stats, "Away Jammer");
+        assert_eq!(away_jammer.total_score, 13);
+        assert_eq!(away_jammer.net_score, 1);
+
+        assert!(stats.jammers.iter().all(|jammer| jammer.name != "Home Reserve"));
+    }
+
+    #[test]
+    fn counts_lead_and_lost_jams() {
+        let mut entries = get_jam_entries();
+        entries.extend([
+            ("ScoreBoard.Game(g1).Period(1).Jam(1).TeamJam(1).Lead", json!(true)),
+            ("ScoreBoard.Game(g1).Period(1).Jam(2).TeamJam(1).Lead", json!(true)),
+            ("ScoreBoard.Game(g1).Period(1).Jam(2).TeamJam(1).Lost", json!(true)),
+            ("ScoreBoard.Game(g1).Period(1).Jam(3).TeamJam(1).Fielding(Jammer).Skater", json!("h1")),
+            ("ScoreBoard.Game(g1).Period(1).Jam(3).TeamJam(1).Lead", json!(false)),
+            ("ScoreBoard.Game(g1).Period(1).Jam(3).TeamJam(2).Fielding(Jammer).Skater", json!("a1")),
+            ("ScoreBoard.Game(g1).Period(1).Jam(3).TeamJam(2).Lead", json!(false)),
+            ("ScoreBoard.Game(g1).Period(1).Jam(4).TeamJam(1).Fielding(Jammer).Skater", json!("h1")),
+        ]);
+
+        let stats = JammerStats::get_game_stats(&get_game(&entries));
+
+        let home_jammer = get_jammer(&stats, "Home Jammer");
+        assert_eq!(home_jammer.jam_count, 4);
+        assert_eq!(home_jammer.lead_count, 2);
+        assert_eq!(home_jammer.lost_count, 1);
+        assert_eq!(home_jammer.lead_percentage, 50.0);
+
+        let away_jammer = get_jammer(&stats, "Away Jammer");
+        assert_eq!(away_jammer.jam_count, 3);
+        assert_eq!(away_jammer.lead_count, 0);
+        assert_eq!(away_jammer.lost_count, 0);
+        assert_eq!(away_jammer.lead_percentage, 0.0);
+    }
+
+    #[test]
+    fn omits_skaters_without_jams() {
+        let entries = [
+            ("ScoreBoard.Game(g1).Team(1).Skater(h1).Name", json!("Home Jammer")),
+            ("ScoreBoard.Game(g1).Period(1).Jam(1).TeamJam(1).Lead", json!(true)),
+        ];
+
+        let stats = JammerStats::get_game_stats(&get_game(&entries));
+        assert!(stats.jammers.is_empty());
+    }
+
+    fn get_initial_pass_times(times: &InitialPassTimes) -> (Option<f32>, Option<f32>, Option<f32>, usize) {
+        (times.mean_time_to_initial, times.best_time_to_initial, times.worst_time_to_initial, times.no_initial_count)
+    }
+
+    #[test]
+    fn computes_time_to_initial_for_jammers_and_teams() {
+        let mut entries = get_jam_entries();
+        entries.extend([
+            ("ScoreBoard.Game(g1).Period(1).Jam(1).TeamJam(1).ScoringTrip(1).JamClockEnd", json!(8000)),
+            ("ScoreBoard.Game(g1).Period(1).Jam(2).TeamJam(1).ScoringTrip(1).JamClockEnd", json!(12000)),
+            ("ScoreBoard.Game(g1).Period(1).Jam(3).TeamJam(1).Fielding(Jammer).Skater", json!("h1")),
+            ("ScoreBoard.Game(g1).Period(1).Jam(3).TeamJam(1).NoInitial", json!(true)),
+            ("ScoreBoard.Game(g1).Period(1).Jam(3).TeamJam(1).ScoringTrip(1).JamClockEnd", json!(20000)),
+            ("ScoreBoard.Game(g1).Period(1).Jam(4).TeamJam(1).Fielding(Jammer).Skater", json!("h2")),
+            ("ScoreBoard.Game(g1).Period(1).Jam(4).TeamJam(1).ScoringTrip(1).JamClockEnd", json!(6000)),
+        ]);
+
+        let stats = JammerStats::get_game_stats(&get_game(&entries));
+
+        assert_eq!(get_initial_pass_times(&get_jammer(&stats, "Home Jammer").initial_pass_times), (Some(10.0), Some(8.0), Some(12.0), 1));
+        assert_eq!(get_initial_pass_times(&get_jammer(&stats, "Home Reserve").initial_pass_times), (Some(6.0), Some(6.0), Some(6.0), 0));
+        assert_eq!(get_initial_pass_times(&get_jammer(&stats, "Away Jammer").initial_pass_times), (None, None, None, 0));
+
+        assert_eq!(stats.teams[0].team, 1);
+        assert_eq!(get_initial_pass_times(&stats.teams[0].initial_pass_times), (Some(26.0 / 3.0), Some(6.0), Some(12.0), 1));
+        assert_eq!(get_initial_pass_times(&stats.teams[1].initial_pass_times), (None, None, None, 0));
+    }
+
+    #[test]
+    fn ignores_initial_trips_without_end_time() {
+        let mut entries = get_jam_entries();
+        entries.extend([
+            ("ScoreBoard.Game(g1).Period(1).Jam(1).TeamJam(1).ScoringTrip(1).JamClockEnd", json!(0)),
+            ("ScoreBoard.Game(g1).Period(1).Jam(2).TeamJam(1).ScoringTrip(1).JamClockEnd", json!(9000)),
+        ]);
+
+        let stats = JammerStats::get_game_stats(&get_game(&entries));
+
+        assert_eq!(get_initial_pass_times(&get_jammer(&stats, "Home Jammer").initial_pass_times), (Some(9.0), Some(9.0), Some(9.0), 0));
     }
 }
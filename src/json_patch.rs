@@ -0,0 +1,124 @@
+use serde_json::{json, Value};
+
+pub fn diff(from: &Value, to: &Value) -> Vec<Value> {
+    let mut operations = Vec::new();
+    add_operations(&mut operations, "", from, to);
+    operations
+}
+
+fn add_operations(operations: &mut Vec<Value>, path: &str, from: &Value, to: &Value) {
+    match (from, to) {
+        (Value::Object(from_map), Value::Object(to_map)) => {
+            for (key, from_value) in from_map.iter() {
+                let child_path = get_child_path(path, key);
+
+                match to_map.get(key) {
+                    Some(to_value) => add_operations(operations, &child_path, from_value, to_value),
+                    None => operations.push(json!({ "op": "remove", "path": child_path })),
+                }
+            }
+
+            for (key, to_value) in to_map.iter().filter(|(key, _)| !from_map.contains_key(*key)) {
+                operations.push(json!({ "op": "add", "path": get_child_path(path, key), "value": to_value }));
+            }
+        },
+        (Value::Array(from_items), Value::Array(to_items)) => {
+            for (index, (from_item, to_item)) in from_items.iter().zip(to_items.iter()).enumerate() {
+                add_operations(operations, &get_child_path(path, &index.to_string()), from_item, to_item);
+            }
+
+            for (index, to_item) in to_items.iter().enumerate().skip(from_items.len()) {
+                operations.push(json!({ "op": "add", "path": get_child_path(path, &index.to_string()), "value": to_item }));
+            }
+
+            for index in (to_items.len()..from_items.len()).rev() {
+                operations.push(json!({ "op": "remove", "path": get_child_path(path, &index.to_string()) }));
+            }
+        },
+        _ if from == to => { },
+        _ => operations.push(json!({ "op": "replace", "path": path, "value": to })),
+    }
+}
+
+fn get_child_path(path: &str, key: &str) -> String {
+    format!("{}/{}", path, key.replace('~', "~0").replace('/', "~1"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn apply(document: &mut Value, operations: &[Value]) {
+        for operation in operations {
+            let path = operation["path"].as_str().unwrap();
+
+            let (parent_path, key) = match path.rfind('/') {
+                Some(index) => (&path[..index], path[index + 1..].replace("~1", "/").replace("~0", "~")),
+                None => {
+                    *document = operation["value"].clone();
+                    continue;
+                }
+            };
+
+            let parent = document.pointer_mut(parent_path).unwrap();
+
+            match (operation["op"].as_str().unwrap(), parent) {
+                ("add", Value::Object(map)) | ("replace", Value::Object(map)) => { map.insert(key, operation["value"].clone()); },
+                ("remove", Value::Object(map)) => { map.remove(&key).unwrap(); },
+                ("add", Value::Array(items)) => items.insert(key.parse().unwrap(), operation["value"].clone()),
+                ("replace", Value::Array(items)) => items[key.parse::<usize>().unwrap()] = operation["value"].clone(),
+                ("remove", Value::Array(items)) => { items.remove(key.parse().unwrap()); },
+                (op, parent) => panic!("Cannot apply {} to {}", op, parent),
+            }
+        }
+    }
+
+    fn assert_patches(from: Value, to: Value) {
+        let operations = diff(&from, &to);
+
+        let mut patched = from;
+        apply(&mut patched, &operations);
+
+        assert_eq!(patched, to);
+    }
+
+    #[test]
+    fn identical_values_have_no_operations() {
+        assert!(diff(&json!({ "a": [1, 2, { "b": null }] }), &json!({ "a": [1, 2, { "b": null }] })).is_empty());
+    }
+
+    #[test]
+    fn patches_objects() {
+        assert_patches(json!({ "a": 1, "b": 2, "c": { "d": 3 } }), json!({ "a": 1, "c": { "d": 4, "e": 5 }, "f": 6 }));
+        assert_patches(json!({ "a": 1 }), json!({}));
+    }
+
+    #[test]
+    fn patches_arrays() {
+        assert_patches(json!([1, 2, 3]), json!([1, 5]));
+        assert_patches(json!([1, 2, 3, 4, 5]), json!([0]));
+        assert_patches(json!([1]), json!([1, 2, 3]));
+        assert_patches(json!([{ "a": [1, 2] }, 3]), json!([{ "a": [2] }, 3, { "b": 4 }]));
+        assert_patches(json!([1, 2]), json!([]));
+    }
+
+    #[test]
+    fn replaces_values_of_different_types() {
+        assert_patches(json!({ "a": [1] }), json!({ "a": { "0": 1 } }));
+        assert_patches(json!([1, 2]), json!("text"));
+    }
+
+    #[test]
+    fn escapes_pointer_characters() {
+        let from = json!({ "a/b": 1, "c~d": 2, "~1": 3 });
+        let to = json!({ "a/b": 4, "c~d": 5, "e/~f": 6 });
+
+        let operations = diff(&from, &to);
+        let paths: Vec<&str> = operations.iter()
+            .map(|operation| operation["path"].as_str().unwrap())
+            .collect();
+
+        assert_eq!(paths, vec!["/a~1b", "/c~0d", "/~01", "/e~1~0f"]);
+        assert_patches(from, to);
+    }
+}
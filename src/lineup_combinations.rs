@@ -0,0 +1,185 @@
+use std::collections::BTreeMap;
+
+use serde::{Serialize, Deserialize};
+use serde_json::{json, Value};
+
+use crate::{game_stats::GameStats, scoreboard_path::FieldingPosition, game_model::{Game, TeamJam}};
+
+type LineupKey<'a> = (u8, Vec<&'a String>);
+type LineupJams<'a> = Vec<(&'a TeamJam, Option<&'a TeamJam>)>;
+
+#[derive(Serialize, Deserialize, PartialEq)]
+struct LineupCombination {
+    #[serde(rename = "team")]
+    team: u8,
+
+    #[serde(rename = "skaters")]
+    skaters: Vec<String>,
+
+    #[serde(rename = "jamCount")]
+    jam_count: usize,
+
+    #[serde(rename = "pointsFor")]
+    points_for: u64,
+
+    #[serde(rename = "pointsAgainst")]
+    points_against: u64,
+
+    #[serde(rename = "leadPercentage")]
+    lead_percentage: f32,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Default)]
+struct LineupCombinationStates {
+    #[serde(rename = "combinations")]
+    combinations: Vec<LineupCombination>,
+}
+
+pub struct LineupCombinations;
+
+impl LineupCombinations {
+    fn get_game_stats(game: &Game) -> LineupCombinationStates {
+        let mut lineup_jams: BTreeMap<LineupKey, LineupJams> = BTreeMap::new();
+
+        for jam in game.periods.values().flat_map(|period| period.jams.values()) {
+            for (team, team_jam) in jam.team_jams.iter() {
+                let mut skater_ids: Vec<&String> = FieldingPosition::BLOCKERS.iter()
+                    .filter_map(|position| team_jam.get_skater_id(*position))
+                    .collect();
+
+                if skater_ids.is_empty() {
+                    continue;
+                }
+
+                skater_ids.sort();
+
+                let opponent_jam = jam.team_jams.iter()
+                    .find(|(opponent, _)| *opponent != team)
+                    .map(|(_, opponent_jam)| opponent_jam);
+
+                lineup_jams.entry((*team, skater_ids)).or_default().push((team_jam, opponent_jam));
+            }
+        }
+
+        let mut combinations: Vec<LineupCombination> = lineup_jams.into_iter()
+            .map(|((team, skater_ids), jams)| {
+                let skater_name = |skater_id: &String| game.teams.get(&team)
+                    .and_then(|team_details| team_details.skaters.get(skater_id))
+                    .map(|skater| skater.name.clone())
+                    .filter(|name| !name.is_empty())
+                    .unwrap_or_else(|| skater_id.clone());
+
+                let mut skaters: Vec<String> = skater_ids.into_iter().map(skater_name).collect();
+                skaters.sort();
+
+                let points_for = jams.iter()
+                    .fold(0, |score, (team_jam, _)| score + team_jam.jam_score.unwrap_or(0));
+
+                let points_against = jams.iter()
+                    .fold(0, |score, (_, opponent_jam)| score + opponent_jam.and_then(|j| j.jam_score).unwrap_or(0));
+
+                let lead_count = jams.iter()
+                    .filter(|(team_jam, _)| team_jam.has_lead())
+                    .count();
+
+                LineupCombination {
+                    team,
+                    skaters,
+                    jam_count: jams.len(),
+                    points_for,
+                    points_against,
+                    lead_percentage: lead_count as f32 * 100.0 / jams.len() as f32,
+                }
+            })
+            .collect();
+
+        combinations.sort_by(|a, b| a.team.cmp(&b.team).then_with(|| b.jam_count.cmp(&a.jam_count)).then_with(|| a.skaters.cmp(&b.skaters)));
+
+        LineupCombinationStates { combinations }
+    }
+}
+
+impl GameStats for LineupCombinations {
+    const DATA_TYPE: &'static str = "LineupCombinations";
+    const TOPICS: &'static [&'static str] = &[
+        "ScoreBoard.Game(*).Team(*).Skater(*).Name",
+        "ScoreBoard.Game(*).Period(*).Jam(*).TeamJam(*).Fielding(Pivot).Skater",
+        "ScoreBoard.Game(*).Period(*).Jam(*).TeamJam(*).Fielding(Blocker1).Skater",
+        "ScoreBoard.Game(*).Period(*).Jam(*).TeamJam(*).Fielding(Blocker2).Skater",
+        "ScoreBoard.Game(*).Period(*).Jam(*).TeamJam(*).Fielding(Blocker3).Skater",
+        "ScoreBoard.Game(*).Period(*).Jam(*).TeamJam(*).JamScore",
+        "ScoreBoard.Game(*).Period(*).Jam(*).TeamJam(*).Lead",
+    ];
+
+    fn compute_game(game: &Game) -> Value {
+        json!(Self::get_game_stats(game))
+    }
+
+    fn get_empty_state() -> Value {
+        json!(LineupCombinationStates::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use serde_json::json;
+
+    use super::*;
+    use crate::scoreboard_connector::{ScoreboardStateStore, ScoreboardStateUpdate};
+
+    fn get_game(entries: &[(&str, Value)]) -> Game {
+        let mut store = ScoreboardStateStore::new();
+        store.apply_update(ScoreboardStateUpdate {
+            state: entries.iter().map(|(key, value)| (key.to_string(), value.clone())).collect(),
+        });
+
+        let change = store.take_changes();
+        change.model.snapshot_games(&HashSet::from(["g1".to_string()])).remove("g1").unwrap()
+    }
+
+    fn get_jam_entries() -> Vec<(&'static str, Value)> {
+        vec![
+            ("ScoreBoard.Game(g1).Team(1).Skater(h1).Name", json!("Alpha")),
+            ("ScoreBoard.Game(g1).Team(1).Skater(h2).Name", json!("Bravo")),
+            ("ScoreBoard.Game(g1).Period(1).Jam(1).TeamJam(1).Fielding(Pivot).Skater", json!("h1")),
+            ("ScoreBoard.Game(g1).Period(1).Jam(1).TeamJam(1).Fielding(Blocker1).Skater", json!("h2")),
+            ("ScoreBoard.Game(g1).Period(1).Jam(1).TeamJam(1).JamScore", json!(4)),
+            ("ScoreBoard.Game(g1).Period(1).Jam(1).TeamJam(1).Lead", json!(true)),
+            ("ScoreBoard.Game(g1).Period(1).Jam(1).TeamJam(2).JamScore", json!(1)),
+            ("ScoreBoard.Game(g1).Period(1).Jam(2).TeamJam(1).Fielding(Blocker2).Skater", json!("h1")),
+            ("ScoreBoard.Game(g1).Period(1).Jam(2).TeamJam(1).Fielding(Blocker3).Skater", json!("h2")),
+            ("ScoreBoard.Game(g1).Period(1).Jam(2).TeamJam(1).JamScore", json!(0)),
+            ("ScoreBoard.Game(g1).Period(1).Jam(2).TeamJam(2).JamScore", json!(8)),
+        ]
+    }
+
+    fn get_combinations(game: &Game) -> Vec<(u8, Vec<String>, usize, u64, u64, f32)> {
+        LineupCombinations::get_game_stats(game).combinations.into_iter()
+            .map(|c| (c.team, c.skaters, c.jam_count, c.points_for, c.points_against, c.lead_percentage))
+            .collect()
+    }
+
+    #[test]
+    fn groups_jams_by_lineup_regardless_of_position() {
+        assert_eq!(get_combinations(&get_game(&get_jam_entries())), vec![
+            (1, vec!["Alpha".to_string(), "Bravo".to_string()], 2, 4, 9, 50.0),
+        ]);
+    }
+
+    #[test]
+    fn ignores_unknown_team_numbers() {
+        let mut entries = get_jam_entries();
+        entries.push(("ScoreBoard.Game(g1).Period(1).Jam(1).TeamJam(4).Fielding(Pivot).Skater", json!("x1")));
+
+        let mut game = get_game(&entries);
+        assert_eq!(get_combinations(&game), get_combinations(&get_game(&get_jam_entries())));
+
+        let jam = game.periods.get_mut(&1).unwrap().jams.get_mut(&1).unwrap();
+        let team_jam = jam.team_jams.remove(&1).unwrap();
+        jam.team_jams.insert(4, team_jam);
+
+        LineupCombinations::get_game_stats(&game);
+    }
+}
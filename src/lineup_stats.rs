@@ -0,0 +1,176 @@
+use serde::{Serialize, Deserialize};
+use serde_json::{json, Value};
+
+use crate::{game_stats::GameStats, scoreboard_path::FieldingPosition, game_model::{Game, TeamJam}};
+
+#[derive(Serialize, Deserialize, PartialEq)]
+struct SkaterLineupInfo {
+    #[serde(rename = "name")]
+    name: String,
+
+    #[serde(rename = "team")]
+    team: u8,
+
+    #[serde(rename = "jamCount")]
+    jam_count: usize,
+
+    #[serde(rename = "pivotJamCount")]
+    pivot_jam_count: usize,
+
+    #[serde(rename = "blockerJamCount")]
+    blocker_jam_count: usize,
+
+    #[serde(rename = "pointsFor")]
+    points_for: u64,
+
+    #[serde(rename = "pointsAgainst")]
+    points_against: u64,
+
+    #[serde(rename = "netPoints")]
+    net_points: i64,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Default)]
+struct LineupStatsStates {
+    #[serde(rename = "skaters")]
+    skaters: Vec<SkaterLineupInfo>,
+}
+
+pub struct LineupStats;
+
+impl LineupStats {
+    fn get_game_stats(game: &Game) -> LineupStatsStates {
+        let team_jams: Vec<(u8, &TeamJam, Option<&TeamJam>)> = game.periods.values()
+            .flat_map(|period| period.jams.values())
+            .flat_map(|jam| jam.team_jams.iter()
+                .map(|(team, team_jam)| (*team, team_jam, jam.team_jams.iter()
+                    .find(|(opponent, _)| **opponent != *team)
+                    .map(|(_, opponent_jam)| opponent_jam))))
+            .collect();
+
+        let mut skaters: Vec<SkaterLineupInfo> = game.teams.iter()
+            .flat_map(|(team, team_details)| team_details.skaters.iter().map(|(skater_id, skater)| (*team, skater_id, skater)))
+            .filter(|(_, _, skater)| !skater.name.is_empty())
+            .map(|(team, skater_id, skater)| {
+                let skater_jams: Vec<(FieldingPosition, &TeamJam, Option<&TeamJam>)> = team_jams.iter()
+                    .filter(|(jam_team, _, _)| *jam_team == team)
+                    .filter_map(|(_, team_jam, opponent_jam)| FieldingPosition::BLOCKERS.iter()
+                        .find(|position| team_jam.get_skater_id(**position) == Some(skater_id))
+                        .map(|position| (*position, *team_jam, *opponent_jam)))
+                    .collect();
+
+                let pivot_jam_count = skater_jams.iter()
+                    .filter(|(position, _, _)| *position == FieldingPosition::Pivot)
+                    .count();
+
+                let points_for = skater_jams.iter()
+                    .fold(0, |score, (_, team_jam, _)| score + team_jam.jam_score.unwrap_or(0));
+
+                let points_against = skater_jams.iter()
+                    .fold(0, |score, (_, _, opponent_jam)| score + opponent_jam.and_then(|j| j.jam_score).unwrap_or(0));
+
+                SkaterLineupInfo {
+                    name: skater.name.clone(),
+                    team,
+                    jam_count: skater_jams.len(),
+                    pivot_jam_count,
+                    blocker_jam_count: skater_jams.len() - pivot_jam_count,
+                    points_for,
+                    points_against,
+                    net_points: points_for as i64 - points_against as i64,
+                }
+            })
+            .filter(|stats| stats.jam_count > 0)
+            .collect();
+
+        skaters.sort_by(|a, b| a.team.cmp(&b.team).then_with(|| a.name.cmp(&b.name)));
+
+        LineupStatsStates { skaters }
+    }
+}
+
+impl GameStats for LineupStats {
+    const DATA_TYPE: &'static str = "LineupStats";
+    const TOPICS: &'static [&'static str] = &[
+        "ScoreBoard.Game(*).Team(*).Skater(*).Name",
+        "ScoreBoard.Game(*).Period(*).Jam(*).TeamJam(*).Fielding(Pivot).Skater",
+        "ScoreBoard.Game(*).Period(*).Jam(*).TeamJam(*).Fielding(Blocker1).Skater",
+        "ScoreBoard.Game(*).Period(*).Jam(*).TeamJam(*).Fielding(Blocker2).Skater",
+        "ScoreBoard.Game(*).Period(*).Jam(*).TeamJam(*).Fielding(Blocker3).Skater",
+        "ScoreBoard.Game(*).Period(*).Jam(*).TeamJam(*).JamScore",
+    ];
+
+    fn compute_game(game: &Game) -> Value {
+        json!(Self::get_game_stats(game))
+    }
+
+    fn get_empty_state() -> Value {
+        json!(LineupStatsStates::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use serde_json::json;
+
+    use super::*;
+    use crate::scoreboard_connector::{ScoreboardStateStore, ScoreboardStateUpdate};
+
+    fn get_game(entries: &[(&str, Value)]) -> Game {
+        let mut store = ScoreboardStateStore::new();
+        store.apply_update(ScoreboardStateUpdate {
+            state: entries.iter().map(|(key, value)| (key.to_string(), value.clone())).collect(),
+        });
+
+        let change = store.take_changes();
+        change.model.snapshot_games(&HashSet::from(["g1".to_string()])).remove("g1").unwrap()
+    }
+
+    fn get_jam_entries() -> Vec<(&'static str, Value)> {
+        vec![
+            ("ScoreBoard.Game(g1).Team(1).Skater(h1).Name", json!("Home Pivot")),
+            ("ScoreBoard.Game(g1).Team(1).Skater(h2).Name", json!("Home Blocker")),
+            ("ScoreBoard.Game(g1).Team(2).Skater(a1).Name", json!("Away Blocker")),
+            ("ScoreBoard.Game(g1).Period(1).Jam(1).TeamJam(1).Fielding(Pivot).Skater", json!("h1")),
+            ("ScoreBoard.Game(g1).Period(1).Jam(1).TeamJam(1).Fielding(Blocker1).Skater", json!("h2")),
+            ("ScoreBoard.Game(g1).Period(1).Jam(1).TeamJam(2).Fielding(Blocker1).Skater", json!("a1")),
+            ("ScoreBoard.Game(g1).Period(1).Jam(1).TeamJam(1).JamScore", json!(8)),
+            ("ScoreBoard.Game(g1).Period(1).Jam(1).TeamJam(2).JamScore", json!(3)),
+            ("ScoreBoard.Game(g1).Period(1).Jam(2).TeamJam(1).Fielding(Blocker2).Skater", json!("h2")),
+            ("ScoreBoard.Game(g1).Period(1).Jam(2).TeamJam(1).JamScore", json!(0)),
+            ("ScoreBoard.Game(g1).Period(1).Jam(2).TeamJam(2).JamScore", json!(4)),
+        ]
+    }
+
+    fn get_skater_stats(game: &Game) -> Vec<(String, usize, usize, u64, u64, i64)> {
+        LineupStats::get_game_stats(game).skaters.into_iter()
+            .map(|s| (s.name, s.jam_count, s.pivot_jam_count, s.points_for, s.points_against, s.net_points))
+            .collect()
+    }
+
+    #[test]
+    fn computes_plus_minus_per_blocker() {
+        assert_eq!(get_skater_stats(&get_game(&get_jam_entries())), vec![
+            ("Home Blocker".to_string(), 2, 0, 8, 7, 1),
+            ("Home Pivot".to_string(), 1, 1, 8, 3, 5),
+            ("Away Blocker".to_string(), 1, 0, 3, 8, -5),
+        ]);
+    }
+
+    #[test]
+    fn ignores_unknown_team_numbers() {
+        let mut entries = get_jam_entries();
+        entries.push(("ScoreBoard.Game(g1).Period(1).Jam(1).TeamJam(4).JamScore", json!(20)));
+
+        let mut game = get_game(&entries);
+        assert_eq!(get_skater_stats(&game), get_skater_stats(&get_game(&get_jam_entries())));
+
+        let jam = game.periods.get_mut(&1).unwrap().jams.get_mut(&2).unwrap();
+        let team_jam = jam.team_jams.remove(&1).unwrap();
+        jam.team_jams.insert(4, team_jam);
+
+        LineupStats::get_game_stats(&game);
+    }
+}
@@ -1,20 +1,47 @@
 mod scoreboard_connector;
+mod scoreboard_path;
 mod socket_server;
 mod cumulative_score;
 mod penalties_by_type;
 mod jammer_stats;
 mod game_info;
+mod scoreboard_status;
+mod session_recorder;
+mod scoreboard_replay;
+mod scoreboard_source;
+mod game_model;
+mod client_queue;
+mod server_diagnostics;
+mod json_patch;
+mod scoring_trips;
+mod power_jams;
+mod box_trips;
+mod skater_penalties;
+mod lineup_stats;
+mod lineup_combinations;
+mod game_stats;
 
 use clap::Parser;
 use simplelog::{CombinedLogger, TermLogger, Config, TerminalMode, ColorChoice};
 use log::{info, LevelFilter};
 
 use crate::{
+    box_trips::BoxTrips,
     cumulative_score::CumulativeScore,
     game_info::GameInfo,
+    game_stats::GameStatsProvider,
     jammer_stats::JammerStats,
+    lineup_combinations::LineupCombinations,
+    lineup_stats::LineupStats,
     penalties_by_type::PenaltiesByType,
+    power_jams::PowerJams,
     scoreboard_connector::ScoreboardConnection,
+    scoreboard_replay::{ScoreboardReplay, ReplaySpeed},
+    scoreboard_source::ScoreboardSource,
+    scoreboard_status::ScoreboardStatus,
+    scoring_trips::ScoringTrips,
+    session_recorder::SessionRecorder,
+    skater_penalties::SkaterPenalties,
     socket_server::SocketServer,
 };
 
@@ -29,6 +56,15 @@ struct CommandLineArguments {
 
     #[arg(long = "logLevel", default_value = "info")]
     log_level: String,
+
+    #[arg(long = "record")]
+    record_path: Option<String>,
+
+    #[arg(long = "replay", conflicts_with = "record_path")]
+    replay_path: Option<String>,
+
+    #[arg(long = "replaySpeed", default_value = "1", requires = "replay_path", value_parser = ReplaySpeed::parse)]
+    replay_speed: ReplaySpeed,
 }
 
 #[tokio::main]
@@ -43,18 +79,35 @@ async fn main() {
         ]
     ).unwrap();
 
-    info!("Connecting to scoreboard");
+    let mut scoreboard: Box<dyn ScoreboardSource> = if let Some(replay_path) = arguments.replay_path {
+        info!("Replaying scoreboard session from {}", replay_path);
 
-    let scoreboard_socket_url = format!("ws://{}/WS", arguments.scoreboard_url);
+        Box::new(ScoreboardReplay::new(replay_path.as_str(), arguments.replay_speed).unwrap())
+    } else {
+        info!("Connecting to scoreboard");
 
-    let mut scoreboard_connection = ScoreboardConnection::new(scoreboard_socket_url).unwrap();
+        let scoreboard_socket_url = format!("ws://{}/WS", arguments.scoreboard_url);
+
+        let recorder = arguments.record_path.map(|path| SessionRecorder::new(path).unwrap());
+
+        Box::new(ScoreboardConnection::new(scoreboard_socket_url, recorder).unwrap())
+    };
 
     info!("Starting API endpoints");
     let mut server = SocketServer::new();
-    CumulativeScore::new(&mut scoreboard_connection, &mut server).await;
-    PenaltiesByType::new(&mut scoreboard_connection, &mut server).await;
-    JammerStats::new(&mut scoreboard_connection, &mut server).await;
-    GameInfo::new(&mut scoreboard_connection, &mut server).await;
+    ScoreboardStatus::register(scoreboard.as_mut(), &mut server).await;
+    GameStatsProvider::<CumulativeScore>::register(scoreboard.as_mut(), &mut server).await;
+    GameStatsProvider::<PenaltiesByType>::register(scoreboard.as_mut(), &mut server).await;
+    GameStatsProvider::<JammerStats>::register(scoreboard.as_mut(), &mut server).await;
+    GameStatsProvider::<ScoringTrips>::register(scoreboard.as_mut(), &mut server).await;
+    GameStatsProvider::<PowerJams>::register(scoreboard.as_mut(), &mut server).await;
+    GameStatsProvider::<BoxTrips>::register(scoreboard.as_mut(), &mut server).await;
+    GameStatsProvider::<SkaterPenalties>::register(scoreboard.as_mut(), &mut server).await;
+    GameStatsProvider::<LineupStats>::register(scoreboard.as_mut(), &mut server).await;
+    GameStatsProvider::<LineupCombinations>::register(scoreboard.as_mut(), &mut server).await;
+    GameInfo::register(scoreboard.as_mut(), &mut server).await;
+
+    scoreboard.start();
 
     server.listen(arguments.host_port).await;
 }
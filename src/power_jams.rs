@@ -0,0 +1,244 @@
+use serde::{Serialize, Deserialize};
+use serde_json::{json, Value};
+
+use crate::{game_stats::GameStats, scoreboard_path::FieldingPosition, game_model::{Game, Jam}};
+
+#[derive(Serialize, Deserialize, PartialEq)]
+struct PowerJam {
+    #[serde(rename = "periodNumber")]
+    period_number: u8,
+
+    #[serde(rename = "jamNumber")]
+    jam_number: u32,
+
+    #[serde(rename = "team")]
+    team: u8,
+
+    #[serde(rename = "pointsFor")]
+    points_for: u64,
+
+    #[serde(rename = "pointsAgainst")]
+    points_against: u64,
+}
+
+#[derive(Serialize, Deserialize, PartialEq)]
+struct TeamPowerJams {
+    #[serde(rename = "team")]
+    team: u8,
+
+    #[serde(rename = "powerJamCount")]
+    power_jam_count: usize,
+
+    #[serde(rename = "pointsFor")]
+    points_for: u64,
+
+    #[serde(rename = "pointsAgainst")]
+    points_against: u64,
+
+    #[serde(rename = "netPointsPerPowerJam")]
+    net_points_per_power_jam: f32,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Default)]
+struct PowerJamStates {
+    #[serde(rename = "powerJams")]
+    power_jams: Vec<PowerJam>,
+
+    #[serde(rename = "teams")]
+    teams: Vec<TeamPowerJams>,
+}
+
+pub struct PowerJams;
+
+impl PowerJams {
+    fn get_game_stats(game: &Game) -> PowerJamStates {
+        let power_jams: Vec<PowerJam> = game.periods.iter()
+            .flat_map(|(period_number, period)| period.jams.iter()
+                .filter_map(|(jam_number, jam)| Self::get_power_jam_team(game, *period_number, *jam_number, jam).map(|team| {
+                    let jam_score = |team| jam.team_jams.get(&team).and_then(|tj| tj.jam_score).unwrap_or(0);
+
+                    PowerJam {
+                        period_number: *period_number,
+                        jam_number: *jam_number,
+                        team,
+                        points_for: jam_score(team),
+                        points_against: jam_score(3 - team),
+                    }
+                })))
+            .collect();
+
+        let teams = game.teams.keys()
+            .map(|team| {
+                let team_power_jams: Vec<&PowerJam> = power_jams.iter()
+                    .filter(|power_jam| power_jam.team == *team)
+                    .collect();
+
+                let points_for = team_power_jams.iter().map(|power_jam| power_jam.points_for).sum();
+                let points_against = team_power_jams.iter().map(|power_jam| power_jam.points_against).sum();
+
+                let net_points_per_power_jam = if team_power_jams.is_empty() {
+                    0.0
+                } else {
+                    (points_for as i64 - points_against as i64) as f32 / team_power_jams.len() as f32
+                };
+
+                TeamPowerJams {
+                    team: *team,
+                    power_jam_count: team_power_jams.len(),
+                    points_for,
+                    points_against,
+                    net_points_per_power_jam,
+                }
+            })
+            .collect();
+
+        PowerJamStates { power_jams, teams }
+    }
+
+    fn get_power_jam_team(game: &Game, period_number: u8, jam_number: u32, jam: &Jam) -> Option<u8> {
+        let jammer_in_box = |team| {
+            let jammer_id = match jam.team_jams.get(&team).and_then(|tj| tj.get_skater_id(FieldingPosition::Jammer)) {
+                Some(id) => id,
+                None => return false,
+            };
+
+            game.teams.get(&team)
+                .map(|team_details| team_details.box_trips.values()
+                    .any(|box_trip| box_trip.skater_id.as_ref() == Some(jammer_id) && box_trip.covers_jam(period_number, jam_number)))
+                .unwrap_or(false)
+        };
+
+        match (jammer_in_box(1), jammer_in_box(2)) {
+            (false, true) => Some(1),
+            (true, false) => Some(2),
+            _ => None,
+        }
+    }
+}
+
+impl GameStats for PowerJams {
+    const DATA_TYPE: &'static str = "PowerJams";
+    const TOPICS: &'static [&'static str] = &[
+        "ScoreBoard.Game(*).Team(*).Name",
+        "ScoreBoard.Game(*).Team(*).BoxTrip(*).CurrentSkater",
+        "ScoreBoard.Game(*).Team(*).BoxTrip(*).StartPeriodNumber",
+        "ScoreBoard.Game(*).Team(*).BoxTrip(*).StartJamNumber",
+        "ScoreBoard.Game(*).Team(*).BoxTrip(*).StartBetweenJams",
+        "ScoreBoard.Game(*).Team(*).BoxTrip(*).EndPeriodNumber",
+        "ScoreBoard.Game(*).Team(*).BoxTrip(*).EndJamNumber",
+        "ScoreBoard.Game(*).Period(*).Jam(*).TeamJam(*).Fielding(Jammer).Skater",
+        "ScoreBoard.Game(*).Period(*).Jam(*).TeamJam(*).JamScore",
+    ];
+
+    fn compute_game(game: &Game) -> Value {
+        json!(Self::get_game_stats(game))
+    }
+
+    fn get_empty_state() -> Value {
+        json!(PowerJamStates::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::{game_model::GameModel, scoreboard_path::ScoreboardPath};
+
+    fn get_game(entries: &[(&str, Value)]) -> Game {
+        let mut model = GameModel::default();
+
+        for (key, value) in entries {
+            model.apply_change(&ScoreboardPath::parse(key).unwrap(), value).unwrap();
+        }
+
+        model.games.remove("g1").unwrap()
+    }
+
+    fn get_jam_entries() -> Vec<(&'static str, Value)> {
+        vec![
+            ("ScoreBoard.Game(g1).Team(1).Name", json!("Home")),
+            ("ScoreBoard.Game(g1).Team(2).Name", json!("Away")),
+            ("ScoreBoard.Game(g1).Period(1).Jam(1).TeamJam(1).Fielding(Jammer).Skater", json!("h1")),
+            ("ScoreBoard.Game(g1).Period(1).Jam(1).TeamJam(2).Fielding(Jammer).Skater", json!("a1")),
+            ("ScoreBoard.Game(g1).Period(1).Jam(2).TeamJam(1).Fielding(Jammer).Skater", json!("h1")),
+            ("ScoreBoard.Game(g1).Period(1).Jam(2).TeamJam(2).Fielding(Jammer).Skater", json!("a2")),
+            ("ScoreBoard.Game(g1).Period(1).Jam(2).TeamJam(1).JamScore", json!(0)),
+            ("ScoreBoard.Game(g1).Period(1).Jam(2).TeamJam(2).JamScore", json!(12)),
+        ]
+    }
+
+    fn get_power_jams(game: &Game) -> Vec<(u8, u32, u8)> {
+        PowerJams::get_game_stats(game).power_jams.iter()
+            .map(|power_jam| (power_jam.period_number, power_jam.jam_number, power_jam.team))
+            .collect()
+    }
+
+    #[test]
+    fn counts_jams_after_jammer_is_released() {
+        let mut entries = get_jam_entries();
+        entries.extend([
+            ("ScoreBoard.Game(g1).Team(1).BoxTrip(b1).CurrentSkater", json!("h1")),
+            ("ScoreBoard.Game(g1).Team(1).BoxTrip(b1).StartPeriodNumber", json!(1)),
+            ("ScoreBoard.Game(g1).Team(1).BoxTrip(b1).StartJamNumber", json!(2)),
+            ("ScoreBoard.Game(g1).Team(1).BoxTrip(b1).StartBetweenJams", json!(false)),
+            ("ScoreBoard.Game(g1).Team(1).BoxTrip(b1).EndPeriodNumber", json!(1)),
+            ("ScoreBoard.Game(g1).Team(1).BoxTrip(b1).EndJamNumber", json!(2)),
+        ]);
+
+        let game = get_game(&entries);
+        assert_eq!(get_power_jams(&game), vec![(1, 2, 2)]);
+
+        let stats = PowerJams::get_game_stats(&game);
+        assert_eq!(stats.teams[1].points_for, 12);
+        assert_eq!(stats.teams[1].points_against, 0);
+    }
+
+    #[test]
+    fn counts_ongoing_box_trips() {
+        let mut entries = get_jam_entries();
+        entries.extend([
+            ("ScoreBoard.Game(g1).Team(2).BoxTrip(b1).CurrentSkater", json!("a1")),
+            ("ScoreBoard.Game(g1).Team(2).BoxTrip(b1).StartPeriodNumber", json!(1)),
+            ("ScoreBoard.Game(g1).Team(2).BoxTrip(b1).StartJamNumber", json!(1)),
+            ("ScoreBoard.Game(g1).Team(2).BoxTrip(b1).EndPeriodNumber", json!(0)),
+            ("ScoreBoard.Game(g1).Team(2).BoxTrip(b1).EndJamNumber", json!(0)),
+        ]);
+
+        assert_eq!(get_power_jams(&get_game(&entries)), vec![(1, 1, 1)]);
+    }
+
+    #[test]
+    fn trips_started_between_jams_count_from_next_jam() {
+        let mut entries = get_jam_entries();
+        entries.extend([
+            ("ScoreBoard.Game(g1).Team(1).BoxTrip(b1).CurrentSkater", json!("h1")),
+            ("ScoreBoard.Game(g1).Team(1).BoxTrip(b1).StartPeriodNumber", json!(1)),
+            ("ScoreBoard.Game(g1).Team(1).BoxTrip(b1).StartJamNumber", json!(1)),
+            ("ScoreBoard.Game(g1).Team(1).BoxTrip(b1).StartBetweenJams", json!(true)),
+            ("ScoreBoard.Game(g1).Team(1).BoxTrip(b1).EndPeriodNumber", json!(1)),
+            ("ScoreBoard.Game(g1).Team(1).BoxTrip(b1).EndJamNumber", json!(2)),
+        ]);
+
+        assert_eq!(get_power_jams(&get_game(&entries)), vec![(1, 2, 2)]);
+    }
+
+    #[test]
+    fn ignores_box_trips_of_other_skaters_and_both_jammers_in_box() {
+        let mut entries = get_jam_entries();
+        entries.extend([
+            ("ScoreBoard.Game(g1).Team(1).BoxTrip(b1).CurrentSkater", json!("h2")),
+            ("ScoreBoard.Game(g1).Team(1).BoxTrip(b1).StartPeriodNumber", json!(1)),
+            ("ScoreBoard.Game(g1).Team(1).BoxTrip(b1).StartJamNumber", json!(1)),
+            ("ScoreBoard.Game(g1).Team(1).BoxTrip(b2).CurrentSkater", json!("h1")),
+            ("ScoreBoard.Game(g1).Team(1).BoxTrip(b2).StartPeriodNumber", json!(1)),
+            ("ScoreBoard.Game(g1).Team(1).BoxTrip(b2).StartJamNumber", json!(2)),
+            ("ScoreBoard.Game(g1).Team(2).BoxTrip(b3).CurrentSkater", json!("a2")),
+            ("ScoreBoard.Game(g1).Team(2).BoxTrip(b3).StartPeriodNumber", json!(1)),
+            ("ScoreBoard.Game(g1).Team(2).BoxTrip(b3).StartJamNumber", json!(2)),
+        ]);
+
+        assert!(get_power_jams(&get_game(&entries)).is_empty());
+    }
+}
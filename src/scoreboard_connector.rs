@@ -1,69 +1,193 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
 use std::{thread, net::TcpStream};
 
-use log::{debug, info, warn, trace};
+use log::{debug, error, info, warn, trace};
 use serde::{Serialize, Deserialize};
 use serde_json::{json, Value};
 
-use tokio::sync::broadcast::{self, Sender, Receiver};
+use tokio::sync::broadcast::{self, error::{RecvError, TryRecvError}};
+use tokio::sync::watch;
 use websocket::OwnedMessage;
+use websocket::url::Url;
 use websocket::{
     ClientBuilder,
-    sync::Writer,
+    sync::{Reader, Writer},
     Message
 };
 
+use crate::{
+    session_recorder::SessionRecorder,
+    scoreboard_path::ScoreboardPath,
+    scoreboard_source::{ScoreboardSource, MemoryScoreboard},
+    game_model::SharedGameModel,
+};
+
+const INITIAL_RECONNECT_DELAY: Duration = Duration::from_secs(1);
+const MAX_RECONNECT_DELAY: Duration = Duration::from_secs(30);
+const ATTEMPTS_BEFORE_FAILED: u32 = 5;
+
 pub type ScoreboardState = HashMap<String, Value>;
 
-struct ScoreboardStateStore {
-    pub state: ScoreboardState,
+pub struct ScoreboardStateStore {
+    model: SharedGameModel,
+    updated_game_ids: HashSet<String>,
+    is_reset: bool,
+}
+
+#[derive(Clone)]
+pub struct ScoreboardStateChange {
+    pub updated_game_ids: Arc<HashSet<String>>,
+    pub is_reset: bool,
+    pub model: SharedGameModel,
 }
 
-#[derive(Serialize, Deserialize)]
+pub struct ScoreboardReceiver {
+    receiver: broadcast::Receiver<ScoreboardStateChange>,
+    needs_resync: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
 pub struct ScoreboardStateUpdate {
     pub state: ScoreboardState,
 }
 
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ConnectionStatus {
+    Connecting,
+    Connected,
+    Reconnecting,
+    Failed,
+    Replaying,
+}
+
+struct TopicRegistrations {
+    topics: Vec<String>,
+    socket_writer: Option<Writer<TcpStream>>,
+}
+
 pub struct ScoreboardConnection {
-    socket_writer: Writer<TcpStream>,
-    state_sender: Sender<ScoreboardState>,
+    registrations: Arc<Mutex<TopicRegistrations>>,
+    scoreboard: MemoryScoreboard,
+    connection_thread: Option<ConnectionThread>,
+}
+
+struct ConnectionThread {
+    url: Url,
+    recorder: Option<SessionRecorder>,
 }
 
 impl ScoreboardConnection {
-    pub fn new(url: String) -> Result<ScoreboardConnection, String> {
-        
-        info!("Opening scoreboard websocket connection to {}", url);
-        let (mut receiver, sender) = 
-            ClientBuilder::new(&url).unwrap()
-            .connect_insecure().unwrap()
-            .split().unwrap();
-
-        let (state_sender, _) = broadcast::channel(100);
-        let thread_sender = state_sender.clone();
-
-        let connection = ScoreboardConnection {
-            socket_writer: sender,
-            state_sender,
-        };
+    pub fn new(url: String, recorder: Option<SessionRecorder>) -> Result<ScoreboardConnection, String> {
+        let url = Url::parse(&url).map_err(|e| format!("Invalid scoreboard URL {}: {:?}", url, e))?;
+
+        let scoreboard = MemoryScoreboard::new(ConnectionStatus::Connecting);
+
+        let registrations = Arc::new(Mutex::new(TopicRegistrations {
+            topics: Vec::new(),
+            socket_writer: None,
+        }));
+
+        Ok(ScoreboardConnection {
+            registrations,
+            scoreboard,
+            connection_thread: Some(ConnectionThread { url, recorder }),
+        })
+    }
+
+    fn run(url: Url, registrations: Arc<Mutex<TopicRegistrations>>, scoreboard: MemoryScoreboard, mut recorder: Option<SessionRecorder>) {
+        let mut failed_attempts = 0;
+
+        loop {
+            info!("Opening scoreboard websocket connection to {}", url);
+
+            match Self::connect(&url, &registrations) {
+                Ok(mut receiver) => {
+                    info!("Connected to scoreboard");
+                    failed_attempts = 0;
+                    scoreboard.reset();
+                    scoreboard.set_status(ConnectionStatus::Connected);
+
+                    if let Some(recorder) = recorder.as_mut() {
+                        recorder.record_reset();
+                    }
+
+                    for message in receiver.incoming_messages() {
+                        match message {
+                            Ok(OwnedMessage::Close(_)) => {
+                                warn!("Scoreboard closed the connection");
+                                break;
+                            },
+                            Ok(OwnedMessage::Ping(data)) => {
+                                Self::send_message(&registrations, &Message::pong(data));
+                            },
+                            Ok(OwnedMessage::Text(message_text)) => {
+                                if let Some(recorder) = recorder.as_mut() {
+                                    recorder.record_message(&message_text);
+                                }
 
-        thread::spawn(move || {
-            let mut state = ScoreboardStateStore::new();
-
-            for message in receiver.incoming_messages() {
-                match message {
-                    Ok(m) => {
-                        state.handle_message(m);
-                        thread_sender.send(state.state.clone()).unwrap();
-                    },
-                    _ => {}
-                };
+                                if let Some(update) = ScoreboardStateUpdate::parse(&message_text) {
+                                    scoreboard.apply_update(update);
+                                }
+                            },
+                            Ok(m) => {
+                                warn!("Unexpected message type received: {:?}", m);
+                            },
+                            Err(e) => {
+                                warn!("Error reading from scoreboard connection: {:?}", e);
+                                break;
+                            }
+                        };
+                    }
+
+                    registrations.lock().unwrap().socket_writer = None;
+                },
+                Err(e) => {
+                    failed_attempts += 1;
+                    error!("Unable to connect to scoreboard: {}", e);
+                }
             }
-        });
 
-        Ok(connection)
+            let status = if failed_attempts >= ATTEMPTS_BEFORE_FAILED {
+                ConnectionStatus::Failed
+            } else {
+                ConnectionStatus::Reconnecting
+            };
+            scoreboard.set_status(status);
+
+            let delay = INITIAL_RECONNECT_DELAY
+                .saturating_mul(2u32.saturating_pow(failed_attempts))
+                .min(MAX_RECONNECT_DELAY);
+
+            info!("Reconnecting to scoreboard in {} seconds", delay.as_secs());
+            thread::sleep(delay);
+        }
+    }
+
+    fn connect(url: &Url, registrations: &Mutex<TopicRegistrations>) -> Result<Reader<TcpStream>, String> {
+        let (receiver, mut sender) =
+            ClientBuilder::from_url(url)
+            .connect_insecure().map_err(|e| format!("{:?}", e))?
+            .split().map_err(|e| format!("{:?}", e))?;
+
+        let mut registrations = registrations.lock().unwrap();
+
+        for topic in registrations.topics.iter() {
+            debug!("Re-registering topic {}", topic);
+            sender.send_message(&Self::get_register_message(topic)).map_err(|e| format!("{:?}", e))?;
+        }
+
+        registrations.socket_writer = Some(sender);
+
+        Ok(receiver)
     }
 
-    pub fn register_topic(&mut self, topic_name: &str) {
+    fn send_message(registrations: &Mutex<TopicRegistrations>, message: &Message) {
+        registrations.lock().unwrap().send_message(message);
+    }
+
+    fn get_register_message(topic_name: &str) -> Message<'static> {
         let message_json = json!({
             "action": "Register",
             "paths": [
@@ -71,41 +195,167 @@ impl ScoreboardConnection {
             ]
         });
 
+        Message::text(message_json.to_string())
+    }
+}
+
+impl ScoreboardSource for ScoreboardConnection {
+    fn register_topic(&mut self, topic_name: &str) {
         debug!("Registering topic {}", topic_name);
 
-        self.socket_writer.send_message(&Message::text(message_json.to_string())).unwrap();
+        let mut registrations = self.registrations.lock().unwrap();
+        registrations.topics.push(topic_name.to_string());
+        registrations.send_message(&Self::get_register_message(topic_name));
     }
 
-    pub fn get_receiver(&mut self) -> Receiver<ScoreboardState> {
-        self.state_sender.subscribe()
+    fn get_receiver(&mut self) -> ScoreboardReceiver {
+        self.scoreboard.get_receiver()
     }
-}
 
-impl ScoreboardStateStore {
+    fn get_status_receiver(&self) -> watch::Receiver<ConnectionStatus> {
+        self.scoreboard.get_status_receiver()
+    }
 
-    pub fn new() -> ScoreboardStateStore {
-        ScoreboardStateStore {
-            state: HashMap::new()
+    fn start(&mut self) {
+        if let Some(ConnectionThread { url, recorder }) = self.connection_thread.take() {
+            let registrations = self.registrations.clone();
+            let scoreboard = self.scoreboard.clone();
+
+            thread::spawn(move || Self::run(url, registrations, scoreboard, recorder));
         }
     }
+}
 
-    pub fn handle_message(&mut self, message: OwnedMessage) {
-        let message_text = match message {
-            OwnedMessage::Text(data) => data,
-            _ => {
-                warn!("Unexpected message type received: {:?}", message);
-                return;
+impl TopicRegistrations {
+    fn send_message(&mut self, message: &Message) {
+        if let Some(writer) = self.socket_writer.as_mut() {
+            if let Err(e) = writer.send_message(message) {
+                warn!("Error sending message to scoreboard: {:?}", e);
             }
-        };
+        }
+    }
+}
 
+impl ScoreboardStateUpdate {
+    pub fn parse(message_text: &str) -> Option<ScoreboardStateUpdate> {
         trace!("Update received: {}", message_text);
 
-        let update: ScoreboardStateUpdate = serde_json::from_str(message_text.as_str()).unwrap();
+        match serde_json::from_str(message_text) {
+            Ok(update) => Some(update),
+            Err(e) => {
+                warn!("Ignoring malformed message from scoreboard: {:?}", e);
+                None
+            }
+        }
+    }
+}
+
+impl ScoreboardReceiver {
+    pub fn new(receiver: broadcast::Receiver<ScoreboardStateChange>) -> ScoreboardReceiver {
+        ScoreboardReceiver {
+            receiver,
+            needs_resync: false,
+        }
+    }
+
+    pub async fn recv(&mut self) -> Option<ScoreboardStateChange> {
+        let mut change = self.recv_next().await?;
+
+        // Merge any changes that queued up while the previous one was being processed
+        loop {
+            match self.receiver.try_recv() {
+                Ok(next) => change = change.merge(next),
+                Err(TryRecvError::Lagged(count)) => {
+                    warn!("Missed {} scoreboard updates. Resynchronising from full state", count);
+                    change = Self::get_resync_change(&change.model);
+                },
+                Err(TryRecvError::Empty | TryRecvError::Closed) => return Some(change),
+            }
+        }
+    }
+
+    async fn recv_next(&mut self) -> Option<ScoreboardStateChange> {
+        loop {
+            match self.receiver.recv().await {
+                Ok(change) if self.needs_resync => {
+                    self.needs_resync = false;
+
+                    return Some(Self::get_resync_change(&change.model));
+                },
+                Ok(change) => return Some(change),
+                Err(RecvError::Lagged(count)) => {
+                    warn!("Missed {} scoreboard updates. Resynchronising from full state", count);
+                    self.needs_resync = true;
+                },
+                Err(RecvError::Closed) => return None,
+            }
+        }
+    }
+
+    fn get_resync_change(model: &SharedGameModel) -> ScoreboardStateChange {
+        let game_ids = model.read().games.keys().cloned().collect();
+
+        ScoreboardStateChange {
+            updated_game_ids: Arc::new(game_ids),
+            is_reset: true,
+            model: model.clone(),
+        }
+    }
+}
+
+impl ScoreboardStateChange {
+    fn merge(self, next: ScoreboardStateChange) -> ScoreboardStateChange {
+        let mut updated_game_ids = Arc::unwrap_or_clone(self.updated_game_ids);
+        updated_game_ids.extend(next.updated_game_ids.iter().cloned());
+
+        ScoreboardStateChange {
+            updated_game_ids: Arc::new(updated_game_ids),
+            is_reset: self.is_reset || next.is_reset,
+            model: next.model,
+        }
+    }
+}
+
+impl ScoreboardStateStore {
+
+    pub fn new() -> ScoreboardStateStore {
+        ScoreboardStateStore {
+            model: SharedGameModel::default(),
+            updated_game_ids: HashSet::new(),
+            is_reset: false,
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.model.reset();
+        self.updated_game_ids.clear();
+        self.is_reset = true;
+    }
 
+    pub fn apply_update(&mut self, update: ScoreboardStateUpdate) {
         for (key, value) in update.state {
             trace!("State update received for {}", key);
-            
-            self.state.insert(key, value);
+
+            match ScoreboardPath::parse(&key) {
+                Ok(path) => match self.model.apply_change(&path, &value) {
+                    Ok(Some(game_id)) => { self.updated_game_ids.insert(game_id); },
+                    Ok(None) => { },
+                    Err(e) => warn!("Ignoring state update for {}: {}", key, e),
+                },
+                Err(e) => warn!("Ignoring state update for {}: {}", key, e),
+            }
         }
     }
-}
\ No newline at end of file
+
+    pub fn take_changes(&mut self) -> ScoreboardStateChange {
+        let change = ScoreboardStateChange {
+            updated_game_ids: Arc::new(std::mem::take(&mut self.updated_game_ids)),
+            is_reset: self.is_reset,
+            model: self.model.clone(),
+        };
+
+        self.is_reset = false;
+
+        change
+    }
+}
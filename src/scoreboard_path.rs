@@ -0,0 +1,390 @@
+use std::{fmt, str::FromStr};
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum FieldingPosition {
+    Jammer,
+    Pivot,
+    Blocker1,
+    Blocker2,
+    Blocker3,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub enum ScoreboardPath {
+    CurrentGame {
+        property: String,
+    },
+    PenaltyCode {
+        game_id: String,
+        code: String,
+    },
+    Team {
+        game_id: String,
+        team: u8,
+        property: String,
+    },
+    Skater {
+        game_id: String,
+        team: u8,
+        skater_id: String,
+        property: String,
+    },
+    Penalty {
+        game_id: String,
+        team: u8,
+        skater_id: String,
+        penalty_id: u32,
+        property: String,
+    },
+    BoxTrip {
+        game_id: String,
+        team: u8,
+        box_trip_id: String,
+        property: String,
+    },
+    Period {
+        game_id: String,
+        period: u8,
+        property: String,
+    },
+    Jam {
+        game_id: String,
+        period: u8,
+        jam: u32,
+        property: String,
+    },
+    TeamJam {
+        game_id: String,
+        period: u8,
+        jam: u32,
+        team: u8,
+        property: String,
+    },
+    Fielding {
+        game_id: String,
+        period: u8,
+        jam: u32,
+        team: u8,
+        position: FieldingPosition,
+        property: String,
+    },
+    ScoringTrip {
+        game_id: String,
+        period: u8,
+        jam: u32,
+        team: u8,
+        trip: u32,
+        property: String,
+    },
+    Other,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub enum PathError {
+    Malformed(String),
+    InvalidId { segment: String, id: String },
+}
+
+type Segment<'a> = (&'a str, Option<&'a str>);
+
+impl fmt::Display for PathError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PathError::Malformed(key) => write!(f, "Malformed scoreboard key {}", key),
+            PathError::InvalidId { segment, id } => write!(f, "Invalid ID '{}' for {}", id, segment),
+        }
+    }
+}
+
+impl FieldingPosition {
+    pub const BLOCKERS: [FieldingPosition; 4] = [
+        FieldingPosition::Pivot,
+        FieldingPosition::Blocker1,
+        FieldingPosition::Blocker2,
+        FieldingPosition::Blocker3,
+    ];
+}
+
+impl FromStr for FieldingPosition {
+    type Err = ();
+
+    fn from_str(value: &str) -> Result<FieldingPosition, ()> {
+        match value {
+            "Jammer" => Ok(FieldingPosition::Jammer),
+            "Pivot" => Ok(FieldingPosition::Pivot),
+            "Blocker1" => Ok(FieldingPosition::Blocker1),
+            "Blocker2" => Ok(FieldingPosition::Blocker2),
+            "Blocker3" => Ok(FieldingPosition::Blocker3),
+            _ => Err(()),
+        }
+    }
+}
+
+impl ScoreboardPath {
+    pub fn parse(key: &str) -> Result<ScoreboardPath, PathError> {
+        let segments = Self::split_segments(key)?;
+
+        match segments.as_slice() {
+            [("ScoreBoard", None), ("CurrentGame", None), (property, None)] =>
+                Ok(ScoreboardPath::CurrentGame { property: property.to_string() }),
+            [("ScoreBoard", None), ("Game", Some(game_id)), rest @ ..] =>
+                Self::parse_game_path(game_id, rest),
+            _ => Ok(ScoreboardPath::Other),
+        }
+    }
+
+    fn parse_game_path(game_id: &str, segments: &[Segment]) -> Result<ScoreboardPath, PathError> {
+        let game_id = game_id.to_string();
+
+        let path = match segments {
+            [("PenaltyCode", Some(code))] => ScoreboardPath::PenaltyCode {
+                game_id,
+                code: code.to_string(),
+            },
+            [("Team", Some(team)), (property, None)] => ScoreboardPath::Team {
+                game_id,
+                team: Self::parse_team("Team", team)?,
+                property: property.to_string(),
+            },
+            [("Team", Some(team)), ("Skater", Some(skater_id)), (property, None)] => ScoreboardPath::Skater {
+                game_id,
+                team: Self::parse_team("Team", team)?,
+                skater_id: skater_id.to_string(),
+                property: property.to_string(),
+            },
+            [("Team", Some(team)), ("Skater", Some(skater_id)), ("Penalty", Some(penalty_id)), (property, None)] => ScoreboardPath::Penalty {
+                game_id,
+                team: Self::parse_team("Team", team)?,
+                skater_id: skater_id.to_string(),
+                penalty_id: Self::parse_id("Penalty", penalty_id)?,
+                property: property.to_string(),
+            },
+            [("Team", Some(team)), ("BoxTrip", Some(box_trip_id)), (property, None)] => ScoreboardPath::BoxTrip {
+                game_id,
+                team: Self::parse_team("Team", team)?,
+                box_trip_id: box_trip_id.to_string(),
+                property: property.to_string(),
+            },
+            [("Period", Some(period)), (property, None)] => ScoreboardPath::Period {
+                game_id,
+                period: Self::parse_id("Period", period)?,
+                property: property.to_string(),
+            },
+            [("Period", Some(period)), ("Jam", Some(jam)), rest @ ..] =>
+                Self::parse_jam_path(game_id, Self::parse_id("Period", period)?, Self::parse_id("Jam", jam)?, rest)?,
+            _ => ScoreboardPath::Other,
+        };
+
+        Ok(path)
+    }
+
+    fn parse_jam_path(game_id: String, period: u8, jam: u32, segments: &[Segment]) -> Result<ScoreboardPath, PathError> {
+        let path = match segments {
+            [(property, None)] => ScoreboardPath::Jam {
+                game_id,
+                period,
+                jam,
+                property: property.to_string(),
+            },
+            [("TeamJam", Some(team)), (property, None)] => ScoreboardPath::TeamJam {
+                game_id,
+                period,
+                jam,
+                team: Self::parse_team("TeamJam", team)?,
+                property: property.to_string(),
+            },
+            [("TeamJam", Some(team)), ("Fielding", Some(position)), (property, None)] => ScoreboardPath::Fielding {
+                game_id,
+                period,
+                jam,
+                team: Self::parse_team("TeamJam", team)?,
+                position: Self::parse_id("Fielding", position)?,
+                property: property.to_string(),
+            },
+            [("TeamJam", Some(team)), ("ScoringTrip", Some(trip)), (property, None)] => ScoreboardPath::ScoringTrip {
+                game_id,
+                period,
+                jam,
+                team: Self::parse_team("TeamJam", team)?,
+                trip: Self::parse_id("ScoringTrip", trip)?,
+                property: property.to_string(),
+            },
+            _ => ScoreboardPath::Other,
+        };
+
+        Ok(path)
+    }
+
+    fn parse_id<T: FromStr>(segment: &str, id: &str) -> Result<T, PathError> {
+        id.parse::<T>().map_err(|_| PathError::InvalidId {
+            segment: segment.to_string(),
+            id: id.to_string(),
+        })
+    }
+
+    fn parse_team(segment: &str, id: &str) -> Result<u8, PathError> {
+        match Self::parse_id(segment, id)? {
+            team @ (1 | 2) => Ok(team),
+            _ => Err(PathError::InvalidId {
+                segment: segment.to_string(),
+                id: id.to_string(),
+            }),
+        }
+    }
+
+    fn split_segments(key: &str) -> Result<Vec<Segment<'_>>, PathError> {
+        let mut segments = Vec::new();
+        let mut remaining = key;
+
+        while !remaining.is_empty() {
+            let name_end = remaining.find(['.', '(']).unwrap_or(remaining.len());
+            let name = &remaining[..name_end];
+
+            if name.is_empty() {
+                return Err(PathError::Malformed(key.to_string()));
+            }
+
+            remaining = &remaining[name_end..];
+
+            let id = if let Some(id_start) = remaining.strip_prefix('(') {
+                let id_end = id_start.find(')').ok_or(PathError::Malformed(key.to_string()))?;
+                remaining = &id_start[id_end + 1..];
+                Some(&id_start[..id_end])
+            } else {
+                None
+            };
+
+            segments.push((name, id));
+
+            remaining = match remaining.strip_prefix('.') {
+                Some(r) if !r.is_empty() => r,
+                Some(_) => return Err(PathError::Malformed(key.to_string())),
+                None if remaining.is_empty() => remaining,
+                None => return Err(PathError::Malformed(key.to_string())),
+            };
+        }
+
+        Ok(segments)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_current_game() {
+        assert_eq!(ScoreboardPath::parse("ScoreBoard.CurrentGame.Game"), Ok(ScoreboardPath::CurrentGame { property: "Game".to_string() }));
+    }
+
+    #[test]
+    fn parses_game_paths() {
+        assert_eq!(ScoreboardPath::parse("ScoreBoard.Game(g1).PenaltyCode(B)"), Ok(ScoreboardPath::PenaltyCode {
+            game_id: "g1".to_string(),
+            code: "B".to_string(),
+        }));
+
+        assert_eq!(ScoreboardPath::parse("ScoreBoard.Game(g1).Team(2).Name"), Ok(ScoreboardPath::Team {
+            game_id: "g1".to_string(),
+            team: 2,
+            property: "Name".to_string(),
+        }));
+
+        assert_eq!(ScoreboardPath::parse("ScoreBoard.Game(g1).Team(1).Skater(s1).Penalty(3).Code"), Ok(ScoreboardPath::Penalty {
+            game_id: "g1".to_string(),
+            team: 1,
+            skater_id: "s1".to_string(),
+            penalty_id: 3,
+            property: "Code".to_string(),
+        }));
+
+        assert_eq!(ScoreboardPath::parse("ScoreBoard.Game(g1).Team(1).BoxTrip(b1).StartJamNumber"), Ok(ScoreboardPath::BoxTrip {
+            game_id: "g1".to_string(),
+            team: 1,
+            box_trip_id: "b1".to_string(),
+            property: "StartJamNumber".to_string(),
+        }));
+    }
+
+    #[test]
+    fn parses_jam_paths() {
+        assert_eq!(ScoreboardPath::parse("ScoreBoard.Game(g1).Period(1).Jam(12).Number"), Ok(ScoreboardPath::Jam {
+            game_id: "g1".to_string(),
+            period: 1,
+            jam: 12,
+            property: "Number".to_string(),
+        }));
+
+        assert_eq!(ScoreboardPath::parse("ScoreBoard.Game(g1).Period(2).Jam(3).TeamJam(1).Fielding(Blocker2).Skater"), Ok(ScoreboardPath::Fielding {
+            game_id: "g1".to_string(),
+            period: 2,
+            jam: 3,
+            team: 1,
+            position: FieldingPosition::Blocker2,
+            property: "Skater".to_string(),
+        }));
+
+        assert_eq!(ScoreboardPath::parse("ScoreBoard.Game(g1).Period(1).Jam(4).TeamJam(2).ScoringTrip(2).Score"), Ok(ScoreboardPath::ScoringTrip {
+            game_id: "g1".to_string(),
+            period: 1,
+            jam: 4,
+            team: 2,
+            trip: 2,
+            property: "Score".to_string(),
+        }));
+    }
+
+    #[test]
+    fn ignores_unknown_paths() {
+        for key in [
+            "ScoreBoard.Clock(Period).Time",
+            "ScoreBoard.Game(g1).Team(1).Skater(s1).Penalty(1).Code.Extra",
+            "ScoreBoard.Game(g1).Period(1).Jam(1).TeamJam(1).Fielding(Jammer).Skater.Extra",
+            "Settings.Setting(ScoreBoard.Penalties.NumberToFoulout)",
+        ] {
+            assert_eq!(ScoreboardPath::parse(key), Ok(ScoreboardPath::Other), "{}", key);
+        }
+    }
+
+    #[test]
+    fn rejects_malformed_keys() {
+        for key in [
+            ".ScoreBoard",
+            "ScoreBoard.",
+            "ScoreBoard..Game",
+            "ScoreBoard.Game(g1",
+            "ScoreBoard.Game(g1)Team(1)",
+            "ScoreBoard.(g1).Team(1)",
+        ] {
+            assert_eq!(ScoreboardPath::parse(key), Err(PathError::Malformed(key.to_string())), "{}", key);
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_ids() {
+        assert_eq!(ScoreboardPath::parse("ScoreBoard.Game(g1).Team(x).Name"), Err(PathError::InvalidId {
+            segment: "Team".to_string(),
+            id: "x".to_string(),
+        }));
+
+        assert_eq!(ScoreboardPath::parse("ScoreBoard.Game(g1).Team(3).Skater(s1).Name"), Err(PathError::InvalidId {
+            segment: "Team".to_string(),
+            id: "3".to_string(),
+        }));
+
+        assert_eq!(ScoreboardPath::parse("ScoreBoard.Game(g1).Period(1).Jam(1).TeamJam(4).JamScore"), Err(PathError::InvalidId {
+            segment: "TeamJam".to_string(),
+            id: "4".to_string(),
+        }));
+
+        assert_eq!(ScoreboardPath::parse("ScoreBoard.Game(g1).Period(-1).Jam(1).Number"), Err(PathError::InvalidId {
+            segment: "Period".to_string(),
+            id: "-1".to_string(),
+        }));
+
+        assert_eq!(ScoreboardPath::parse("ScoreBoard.Game(g1).Period(1).Jam(1).TeamJam(1).Fielding(Referee).Skater"), Err(PathError::InvalidId {
+            segment: "Fielding".to_string(),
+            id: "Referee".to_string(),
+        }));
+    }
+}
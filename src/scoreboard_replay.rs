@@ -0,0 +1,351 @@
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::time::Duration;
+use std::thread;
+
+use log::{debug, error, info, warn, trace};
+use tokio::sync::watch;
+
+use crate::{
+    scoreboard_connector::{ScoreboardReceiver, ScoreboardStateUpdate, ConnectionStatus},
+    scoreboard_source::{ScoreboardSource, MemoryScoreboard},
+    session_recorder::{RecordedMessage, RecordedEvent},
+};
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum ReplaySpeed {
+    Multiplier(f64),
+    AsFastAsPossible,
+}
+
+impl ReplaySpeed {
+    pub fn parse(value: &str) -> Result<ReplaySpeed, String> {
+        match value.to_ascii_lowercase().as_str() {
+            "max" => Ok(ReplaySpeed::AsFastAsPossible),
+            v => match v.trim_end_matches('x').parse::<f64>() {
+                Ok(m) if m.is_finite() && m > 0.0 => Ok(ReplaySpeed::Multiplier(m)),
+                _ => Err(format!("Invalid replay speed {}", value)),
+            }
+        }
+    }
+}
+
+#[derive(Debug)]
+enum ReplayCommand {
+    Pause,
+    Resume,
+    Seek(Duration),
+    Speed(ReplaySpeed),
+}
+
+impl ReplayCommand {
+    fn parse(line: &str) -> Result<ReplayCommand, String> {
+        let mut parts = line.split_whitespace();
+
+        match (parts.next().map(|c| c.to_ascii_lowercase()).as_deref(), parts.next()) {
+            (Some("pause"), None) => Ok(ReplayCommand::Pause),
+            (Some("resume") | Some("play"), None) => Ok(ReplayCommand::Resume),
+            (Some("seek"), Some(seconds)) =>
+                seconds.parse::<f64>()
+                    .ok()
+                    .filter(|s| s.is_finite() && *s >= 0.0)
+                    .and_then(|s| Duration::try_from_secs_f64(s).ok())
+                    .map(ReplayCommand::Seek)
+                    .ok_or(format!("Invalid seek position {}", seconds)),
+            (Some("speed"), Some(speed)) => ReplaySpeed::parse(speed).map(ReplayCommand::Speed),
+            _ => Err(format!("Unrecognised replay command '{}'", line.trim())),
+        }
+    }
+}
+
+pub struct ScoreboardReplay {
+    scoreboard: MemoryScoreboard,
+    player: Option<ReplayPlayer>,
+}
+
+struct ReplayPlayer {
+    messages: Vec<RecordedMessage>,
+    speed: ReplaySpeed,
+    paused: bool,
+    position: usize,
+    scoreboard: MemoryScoreboard,
+}
+
+impl ScoreboardReplay {
+    pub fn new(path: &str, speed: ReplaySpeed) -> Result<ScoreboardReplay, String> {
+        let messages = Self::read_recording(path)?;
+
+        info!("Loaded {} messages from {}", messages.len(), path);
+
+        let scoreboard = MemoryScoreboard::new(ConnectionStatus::Replaying);
+
+        Ok(ScoreboardReplay {
+            scoreboard: scoreboard.clone(),
+            player: Some(ReplayPlayer {
+                messages,
+                speed,
+                paused: false,
+                position: 0,
+                scoreboard,
+            }),
+        })
+    }
+
+    fn read_recording(path: &str) -> Result<Vec<RecordedMessage>, String> {
+        let file = File::open(path).map_err(|e| format!("Unable to open recording file {}: {:?}", path, e))?;
+
+        let messages = BufReader::new(file).lines()
+            .enumerate()
+            .filter_map(|(line_number, line)| {
+                let line = match line {
+                    Ok(l) => l,
+                    Err(e) => {
+                        warn!("Unable to read line {} of {}: {:?}", line_number + 1, path, e);
+                        return None;
+                    }
+                };
+
+                if line.trim().is_empty() {
+                    return None;
+                }
+
+                match serde_json::from_str::<RecordedMessage>(&line) {
+                    Ok(m) => Some(m),
+                    Err(e) => {
+                        warn!("Skipping invalid message on line {} of {}: {:?}", line_number + 1, path, e);
+                        None
+                    }
+                }
+            })
+            .collect();
+
+        Ok(messages)
+    }
+}
+
+impl ScoreboardSource for ScoreboardReplay {
+    fn register_topic(&mut self, topic_name: &str) {
+        self.scoreboard.register_topic(topic_name);
+    }
+
+    fn get_receiver(&mut self) -> ScoreboardReceiver {
+        self.scoreboard.get_receiver()
+    }
+
+    fn get_status_receiver(&self) -> watch::Receiver<ConnectionStatus> {
+        self.scoreboard.get_status_receiver()
+    }
+
+    fn start(&mut self) {
+        if let Some(player) = self.player.take() {
+            player.start();
+        }
+    }
+}
+
+impl ReplayPlayer {
+    fn start(mut self) {
+        info!("Replay commands: pause, resume, seek <seconds>, speed <multiplier|max>");
+
+        let (command_sender, command_receiver) = mpsc::channel();
+
+        thread::spawn(move || {
+            for line in std::io::stdin().lines() {
+                let line = match line {
+                    Ok(l) => l,
+                    Err(e) => {
+                        error!("Error reading replay command: {:?}", e);
+                        break;
+                    }
+                };
+
+                if line.trim().is_empty() {
+                    continue;
+                }
+
+                match ReplayCommand::parse(&line) {
+                    Ok(command) => {
+                        if command_sender.send(command).is_err() {
+                            break;
+                        }
+                    },
+                    Err(e) => warn!("{}", e),
+                }
+            }
+        });
+
+        thread::spawn(move || self.run(command_receiver));
+    }
+
+    fn run(&mut self, commands: mpsc::Receiver<ReplayCommand>) {
+        loop {
+            let wait = if self.paused || self.position >= self.messages.len() {
+                None
+            } else {
+                Some(self.get_delay_before_next_message())
+            };
+
+            let command = match wait {
+                None => match commands.recv() {
+                    Ok(c) => Some(c),
+                    Err(_) => {
+                        debug!("Replay command channel closed");
+                        if self.paused || self.position >= self.messages.len() {
+                            return;
+                        }
+                        None
+                    }
+                },
+                Some(delay) if delay.is_zero() => commands.try_recv().ok(),
+                Some(delay) => match commands.recv_timeout(delay) {
+                    Ok(c) => Some(c),
+                    Err(RecvTimeoutError::Timeout) => None,
+                    Err(RecvTimeoutError::Disconnected) => {
+                        thread::sleep(delay);
+                        None
+                    }
+                },
+            };
+
+            if let Some(command) = command {
+                self.handle_command(command);
+                continue;
+            }
+
+            self.play_next_message();
+
+            if self.position == self.messages.len() {
+                info!("Replay complete");
+            }
+        }
+    }
+
+    fn get_delay_before_next_message(&self) -> Duration {
+        let multiplier = match self.speed {
+            ReplaySpeed::AsFastAsPossible => return Duration::ZERO,
+            ReplaySpeed::Multiplier(m) => m,
+        };
+
+        if self.position == 0 {
+            return Duration::ZERO;
+        }
+
+        let gap = self.messages[self.position].received_at.saturating_sub(self.messages[self.position - 1].received_at);
+
+        Duration::try_from_secs_f64(Duration::from_millis(gap).as_secs_f64() / multiplier).unwrap_or(Duration::MAX)
+    }
+
+    fn play_next_message(&mut self) {
+        let message = &self.messages[self.position];
+        self.position += 1;
+
+        trace!("Replaying message {} of {}", self.position, self.messages.len());
+
+        match &message.event {
+            RecordedEvent::Message { message } => {
+                if let Some(update) = ScoreboardStateUpdate::parse(message) {
+                    self.scoreboard.apply_update(update);
+                }
+            },
+            RecordedEvent::Reset => {
+                debug!("Replaying scoreboard reconnect");
+                self.scoreboard.reset();
+            },
+        }
+    }
+
+    fn handle_command(&mut self, command: ReplayCommand) {
+        debug!("Handling replay command {:?}", command);
+
+        match command {
+            ReplayCommand::Pause => {
+                info!("Replay paused");
+                self.paused = true;
+            },
+            ReplayCommand::Resume => {
+                info!("Replay resumed");
+                self.paused = false;
+            },
+            ReplayCommand::Speed(speed) => {
+                info!("Replay speed set to {:?}", speed);
+                self.speed = speed;
+            },
+            ReplayCommand::Seek(position) => self.seek(position),
+        }
+    }
+
+    fn seek(&mut self, position: Duration) {
+        let start_time = match self.messages.first() {
+            Some(m) => m.received_at,
+            None => return,
+        };
+
+        let target_time = start_time.saturating_add(u64::try_from(position.as_millis()).unwrap_or(u64::MAX));
+
+        info!("Seeking replay to {} seconds", position.as_secs());
+
+        self.position = self.messages.iter()
+            .take_while(|m| m.received_at <= target_time)
+            .count();
+
+        let played_messages = &self.messages[..self.position];
+        let last_reset = played_messages.iter()
+            .rposition(|m| m.event == RecordedEvent::Reset)
+            .map_or(0, |i| i + 1);
+
+        self.scoreboard.reset();
+        self.scoreboard.apply_updates(played_messages[last_reset..].iter().filter_map(|m| match &m.event {
+            RecordedEvent::Message { message } => ScoreboardStateUpdate::parse(message),
+            RecordedEvent::Reset => None,
+        }));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_replay_speeds() {
+        assert_eq!(ReplaySpeed::parse("max"), Ok(ReplaySpeed::AsFastAsPossible));
+        assert_eq!(ReplaySpeed::parse("10x"), Ok(ReplaySpeed::Multiplier(10.0)));
+        assert_eq!(ReplaySpeed::parse("0.5"), Ok(ReplaySpeed::Multiplier(0.5)));
+    }
+
+    #[test]
+    fn rejects_invalid_replay_speeds() {
+        for speed in ["0", "-1", "inf", "NaN", "fast", ""] {
+            assert!(ReplaySpeed::parse(speed).is_err(), "{} should be rejected", speed);
+        }
+    }
+
+    #[test]
+    fn parses_seek_commands() {
+        assert!(matches!(ReplayCommand::parse("seek 90"), Ok(ReplayCommand::Seek(d)) if d == Duration::from_secs(90)));
+        assert!(matches!(ReplayCommand::parse("seek 1.5"), Ok(ReplayCommand::Seek(d)) if d == Duration::from_millis(1500)));
+    }
+
+    #[test]
+    fn rejects_out_of_range_seek_positions() {
+        for line in ["seek inf", "seek NaN", "seek 1e30", "seek -5", "seek"] {
+            assert!(ReplayCommand::parse(line).is_err(), "{} should be rejected", line);
+        }
+    }
+
+    #[test]
+    fn small_speeds_do_not_overflow_delay() {
+        let player = ReplayPlayer {
+            messages: vec![
+                RecordedMessage { received_at: 0, event: RecordedEvent::Reset },
+                RecordedMessage { received_at: 1000, event: RecordedEvent::Reset },
+            ],
+            speed: ReplaySpeed::Multiplier(f64::MIN_POSITIVE),
+            paused: false,
+            position: 1,
+            scoreboard: MemoryScoreboard::new(ConnectionStatus::Replaying),
+        };
+
+        assert_eq!(player.get_delay_before_next_message(), Duration::MAX);
+    }
+}
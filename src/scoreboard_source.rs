@@ -0,0 +1,157 @@
+use std::sync::{Arc, Mutex};
+
+use log::trace;
+use tokio::sync::broadcast::{self, Sender};
+use tokio::sync::watch;
+
+use crate::scoreboard_connector::{ScoreboardStateStore, ScoreboardStateUpdate, ScoreboardStateChange, ScoreboardReceiver, ConnectionStatus};
+
+pub trait ScoreboardSource {
+    fn register_topic(&mut self, topic_name: &str);
+    fn get_receiver(&mut self) -> ScoreboardReceiver;
+    fn get_status_receiver(&self) -> watch::Receiver<ConnectionStatus>;
+
+    fn start(&mut self) { }
+}
+
+#[derive(Clone)]
+pub struct MemoryScoreboard {
+    store: Arc<Mutex<ScoreboardStateStore>>,
+    state_sender: Sender<ScoreboardStateChange>,
+    status_sender: Arc<watch::Sender<ConnectionStatus>>,
+}
+
+impl MemoryScoreboard {
+    pub fn new(status: ConnectionStatus) -> MemoryScoreboard {
+        let (state_sender, _) = broadcast::channel(100);
+        let (status_sender, _) = watch::channel(status);
+
+        MemoryScoreboard {
+            store: Arc::new(Mutex::new(ScoreboardStateStore::new())),
+            state_sender,
+            status_sender: Arc::new(status_sender),
+        }
+    }
+
+    pub fn apply_update(&self, update: ScoreboardStateUpdate) {
+        self.apply_updates(std::iter::once(update));
+    }
+
+    pub fn apply_updates(&self, updates: impl Iterator<Item = ScoreboardStateUpdate>) {
+        let mut store = self.store.lock().unwrap();
+
+        for update in updates {
+            store.apply_update(update);
+        }
+
+        if self.state_sender.send(store.take_changes()).is_err() {
+            trace!("No receivers for scoreboard state");
+        }
+    }
+
+    pub fn reset(&self) {
+        self.store.lock().unwrap().reset();
+    }
+
+    pub fn set_status(&self, status: ConnectionStatus) {
+        self.status_sender.send_replace(status);
+    }
+}
+
+impl ScoreboardSource for MemoryScoreboard {
+    fn register_topic(&mut self, topic_name: &str) {
+        trace!("Topic {} registered on in-memory scoreboard", topic_name);
+    }
+
+    fn get_receiver(&mut self) -> ScoreboardReceiver {
+        ScoreboardReceiver::new(self.state_sender.subscribe())
+    }
+
+    fn get_status_receiver(&self) -> watch::Receiver<ConnectionStatus> {
+        self.status_sender.subscribe()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::time::Duration;
+
+    use serde_json::json;
+
+    use super::*;
+
+    fn update(entries: &[(&str, serde_json::Value)]) -> ScoreboardStateUpdate {
+        ScoreboardStateUpdate {
+            state: entries.iter().map(|(k, v)| (k.to_string(), v.clone())).collect::<HashMap<_, _>>(),
+        }
+    }
+
+    #[tokio::test]
+    async fn broadcasts_updated_games() {
+        let mut scoreboard = MemoryScoreboard::new(ConnectionStatus::Connected);
+        let mut receiver = scoreboard.get_receiver();
+
+        scoreboard.apply_update(update(&[("ScoreBoard.Game(g1).Team(1).Name", json!("Home"))]));
+
+        let change = receiver.recv().await.unwrap();
+        assert!(!change.is_reset);
+        assert_eq!(change.updated_game_ids.iter().collect::<Vec<_>>(), vec!["g1"]);
+        assert_eq!(change.model.read().games["g1"].teams[&1].name, "Home");
+    }
+
+    #[tokio::test]
+    async fn batches_updates_into_one_change() {
+        let mut scoreboard = MemoryScoreboard::new(ConnectionStatus::Connected);
+        let mut receiver = scoreboard.get_receiver();
+
+        scoreboard.apply_updates(vec![
+            update(&[("ScoreBoard.Game(g1).Team(1).Name", json!("Home"))]),
+            update(&[("ScoreBoard.Game(g2).Team(1).Name", json!("Away"))]),
+        ].into_iter());
+
+        let change = receiver.recv().await.unwrap();
+        assert_eq!(change.updated_game_ids.len(), 2);
+        assert!(tokio::time::timeout(Duration::from_millis(50), receiver.recv()).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn merges_queued_changes() {
+        let mut scoreboard = MemoryScoreboard::new(ConnectionStatus::Connected);
+        let mut receiver = scoreboard.get_receiver();
+
+        scoreboard.apply_update(update(&[("ScoreBoard.Game(g1).Team(1).Name", json!("Home"))]));
+        scoreboard.reset();
+        scoreboard.apply_update(update(&[("ScoreBoard.Game(g2).Team(1).Name", json!("Away"))]));
+
+        let change = receiver.recv().await.unwrap();
+        assert!(change.is_reset);
+        assert_eq!(change.updated_game_ids.len(), 2);
+        assert!(tokio::time::timeout(Duration::from_millis(50), receiver.recv()).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn reset_clears_model_and_flags_next_change() {
+        let mut scoreboard = MemoryScoreboard::new(ConnectionStatus::Connected);
+        scoreboard.apply_update(update(&[("ScoreBoard.Game(g1).Team(1).Name", json!("Home"))]));
+
+        let mut receiver = scoreboard.get_receiver();
+        scoreboard.reset();
+        scoreboard.apply_update(update(&[("ScoreBoard.Game(g2).Team(1).Name", json!("Away"))]));
+
+        let change = receiver.recv().await.unwrap();
+        assert!(change.is_reset);
+        assert!(!change.model.read().games.contains_key("g1"));
+        assert!(change.model.read().games.contains_key("g2"));
+    }
+
+    #[test]
+    fn publishes_status_changes() {
+        let scoreboard = MemoryScoreboard::new(ConnectionStatus::Connecting);
+        let receiver = scoreboard.get_status_receiver();
+
+        scoreboard.set_status(ConnectionStatus::Connected);
+
+        assert_eq!(*receiver.borrow(), ConnectionStatus::Connected);
+    }
+}
@@ -0,0 +1,61 @@
+use std::sync::Arc;
+
+use tokio::sync::Mutex;
+
+use log::{debug, error};
+use serde_json::json;
+
+use crate::{socket_server::{UpdateProvider, Update, SocketServer}, scoreboard_connector::ConnectionStatus, scoreboard_source::ScoreboardSource};
+
+pub struct ScoreboardStatus {
+    status: ConnectionStatus,
+}
+
+impl ScoreboardStatus {
+    pub async fn register(scoreboard: &mut dyn ScoreboardSource, socket_server: &mut SocketServer) {
+        let mut receiver = scoreboard.get_status_receiver();
+
+        let scoreboard_status = Arc::new(Mutex::new(ScoreboardStatus {
+            status: *receiver.borrow(),
+        }));
+
+        let update_sender = socket_server.get_update_sender();
+        socket_server.register_update_provider("ScoreboardStatus", scoreboard_status.clone()).await;
+
+        tokio::task::spawn(async move {
+            while receiver.changed().await.is_ok() {
+                let status = *receiver.borrow_and_update();
+
+                let mut locked_status = scoreboard_status.lock().await;
+                locked_status.status = status;
+
+                let update = Update {
+                    game_id: "*".to_string(),
+                    data_type: "ScoreboardStatus".to_string(),
+                    update: locked_status.get_state_json(),
+                };
+
+                debug!("Sending ScoreboardStatus update: {:?}", status);
+                if let Err(e) = update_sender.send(update).await {
+                    error!("Error sending update on mpsc: {:?}", e);
+                }
+            }
+        });
+    }
+
+    fn get_state_json(&self) -> serde_json::Value {
+        json!({
+            "status": self.status,
+        })
+    }
+}
+
+impl UpdateProvider for ScoreboardStatus {
+    fn get_state(&self, _game_id: &str) -> Option<serde_json::Value> {
+        Some(self.get_state_json())
+    }
+
+    fn get_empty_state(&self) -> serde_json::Value {
+        self.get_state_json()
+    }
+}
@@ -0,0 +1,293 @@
+use serde::{Serialize, Deserialize};
+use serde_json::{json, Value};
+
+use crate::{game_stats::GameStats, game_model::{Game, TeamJam}};
+
+#[derive(Serialize, Deserialize, PartialEq)]
+struct TripInfo {
+    #[serde(rename = "tripNumber")]
+    trip_number: u32,
+
+    #[serde(rename = "score")]
+    score: u64,
+
+    #[serde(rename = "duration")]
+    duration: Option<f32>,
+
+    #[serde(rename = "afterStarPass")]
+    after_star_pass: bool,
+}
+
+#[derive(Serialize, Deserialize, PartialEq)]
+struct TeamJamTrips {
+    #[serde(rename = "team")]
+    team: u8,
+
+    #[serde(rename = "tripCount")]
+    trip_count: usize,
+
+    #[serde(rename = "trips")]
+    trips: Vec<TripInfo>,
+}
+
+#[derive(Serialize, Deserialize, PartialEq)]
+struct JamTrips {
+    #[serde(rename = "periodNumber")]
+    period_number: u8,
+
+    #[serde(rename = "jamNumber")]
+    jam_number: u32,
+
+    #[serde(rename = "teams")]
+    teams: Vec<TeamJamTrips>,
+}
+
+#[derive(Serialize, Deserialize, PartialEq)]
+struct JammerTrips {
+    #[serde(rename = "name")]
+    name: String,
+
+    #[serde(rename = "team")]
+    team: u8,
+
+    #[serde(rename = "tripCount")]
+    trip_count: usize,
+
+    #[serde(rename = "totalScore")]
+    total_score: u64,
+
+    #[serde(rename = "meanPointsPerTrip")]
+    mean_points_per_trip: f32,
+}
+
+#[derive(Serialize, Deserialize, PartialEq)]
+struct TeamTrips {
+    #[serde(rename = "team")]
+    team: u8,
+
+    #[serde(rename = "tripCount")]
+    trip_count: usize,
+
+    #[serde(rename = "totalScore")]
+    total_score: u64,
+
+    #[serde(rename = "meanPointsPerTrip")]
+    mean_points_per_trip: f32,
+
+    #[serde(rename = "meanTripsPerJam")]
+    mean_trips_per_jam: f32,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Default)]
+struct ScoringTripStates {
+    #[serde(rename = "jams")]
+    jams: Vec<JamTrips>,
+
+    #[serde(rename = "jammers")]
+    jammers: Vec<JammerTrips>,
+
+    #[serde(rename = "teams")]
+    teams: Vec<TeamTrips>,
+}
+
+pub struct ScoringTrips;
+
+impl ScoringTrips {
+    fn get_game_stats(game: &Game) -> ScoringTripStates {
+        let jams: Vec<JamTrips> = game.periods.iter()
+            .flat_map(|(period_number, period)| period.jams.iter()
+                .map(|(jam_number, jam)| JamTrips {
+                    period_number: *period_number,
+                    jam_number: *jam_number,
+                    teams: jam.team_jams.iter()
+                        .map(|(team, team_jam)| {
+                            let trips: Vec<TripInfo> = Self::get_scoring_trips(team_jam).collect();
+
+                            TeamJamTrips {
+                                team: *team,
+                                trip_count: trips.len(),
+                                trips,
+                            }
+                        })
+                        .filter(|team_trips| team_trips.trip_count > 0)
+                        .collect(),
+                }))
+            .filter(|jam_trips| !jam_trips.teams.is_empty())
+            .collect();
+
+        let team_jams: Vec<(u8, &TeamJam)> = game.periods.values()
+            .flat_map(|period| period.jams.values())
+            .flat_map(|jam| jam.team_jams.iter().map(|(team, team_jam)| (*team, team_jam)))
+            .collect();
+
+        let mut jammers: Vec<JammerTrips> = game.teams.iter()
+            .flat_map(|(team, team_details)| team_details.skaters.iter().map(|(skater_id, skater)| (*team, skater_id, skater)))
+            .filter(|(_, _, skater)| !skater.name.is_empty())
+            .map(|(team, skater_id, skater)| {
+                let scores: Vec<u64> = team_jams.iter()
+                    .filter(|(jam_team, _)| *jam_team == team)
+                    .flat_map(|(_, team_jam)| Self::get_scoring_trips(team_jam)
+                        .filter(|trip| team_jam.get_jammer_id(trip.after_star_pass) == Some(skater_id))
+                        .map(|trip| trip.score))
+                    .collect();
+
+                let total_score = scores.iter().sum();
+
+                JammerTrips {
+                    name: skater.name.clone(),
+                    team,
+                    trip_count: scores.len(),
+                    total_score,
+                    mean_points_per_trip: Self::get_mean(total_score, scores.len()),
+                }
+            })
+            .filter(|stats| stats.trip_count > 0)
+            .collect();
+
+        jammers.sort_by(|a, b| a.team.cmp(&b.team).then_with(|| a.name.cmp(&b.name)));
+
+        let teams = game.teams.keys()
+            .map(|team| {
+                let team_jams: Vec<&TeamJam> = team_jams.iter()
+                    .filter(|(jam_team, _)| jam_team == team)
+                    .map(|(_, team_jam)| *team_jam)
+                    .collect();
+
+                let scores: Vec<u64> = team_jams.iter()
+                    .flat_map(|team_jam| Self::get_scoring_trips(team_jam).map(|trip| trip.score))
+                    .collect();
+
+                let total_score = scores.iter().sum();
+
+                TeamTrips {
+                    team: *team,
+                    trip_count: scores.len(),
+                    total_score,
+                    mean_points_per_trip: Self::get_mean(total_score, scores.len()),
+                    mean_trips_per_jam: Self::get_mean(scores.len() as u64, team_jams.len()),
+                }
+            })
+            .collect();
+
+        ScoringTripStates { jams, jammers, teams }
+    }
+
+    fn get_scoring_trips(team_jam: &TeamJam) -> impl Iterator<Item = TripInfo> + '_ {
+        // Trip 1 is the initial pass, which doesn't score
+        team_jam.scoring_trips.iter()
+            .filter(|(trip_number, _)| **trip_number > 1)
+            .filter_map(|(trip_number, trip)| trip.score.map(|score| TripInfo {
+                trip_number: *trip_number,
+                score,
+                duration: trip.duration.map(|duration| duration as f32 / 1000.0),
+                after_star_pass: trip.after_star_pass.unwrap_or(false),
+            }))
+    }
+
+    fn get_mean(total: u64, count: usize) -> f32 {
+        if count == 0 {
+            0.0
+        } else {
+            total as f32 / count as f32
+        }
+    }
+}
+
+impl GameStats for ScoringTrips {
+    const DATA_TYPE: &'static str = "ScoringTrips";
+    const TOPICS: &'static [&'static str] = &[
+        "ScoreBoard.Game(*).Team(*).Skater(*).Name",
+        "ScoreBoard.Game(*).Period(*).Jam(*).TeamJam(*).Fielding(Jammer).Skater",
+        "ScoreBoard.Game(*).Period(*).Jam(*).TeamJam(*).Fielding(Pivot).Skater",
+        "ScoreBoard.Game(*).Period(*).Jam(*).TeamJam(*).ScoringTrip(*).Score",
+        "ScoreBoard.Game(*).Period(*).Jam(*).TeamJam(*).ScoringTrip(*).AfterSP",
+        "ScoreBoard.Game(*).Period(*).Jam(*).TeamJam(*).ScoringTrip(*).Duration",
+    ];
+
+    fn compute_game(game: &Game) -> Value {
+        json!(Self::get_game_stats(game))
+    }
+
+    fn get_empty_state() -> Value {
+        json!(ScoringTripStates::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::{game_model::GameModel, scoreboard_path::ScoreboardPath};
+
+    fn get_game(entries: &[(&str, Value)]) -> Game {
+        let mut model = GameModel::default();
+
+        for (key, value) in entries {
+            model.apply_change(&ScoreboardPath::parse(key).unwrap(), value).unwrap();
+        }
+
+        model.games.remove("g1").unwrap()
+    }
+
+    fn get_jam_entries() -> Vec<(&'static str, Value)> {
+        vec![
+            ("ScoreBoard.Game(g1).Team(1).Skater(h1).Name", json!("Home Jammer")),
+            ("ScoreBoard.Game(g1).Team(1).Skater(h2).Name", json!("Home Pivot")),
+            ("ScoreBoard.Game(g1).Period(1).Jam(1).TeamJam(1).Fielding(Jammer).Skater", json!("h1")),
+            ("ScoreBoard.Game(g1).Period(1).Jam(1).TeamJam(1).Fielding(Pivot).Skater", json!("h2")),
+            ("ScoreBoard.Game(g1).Period(1).Jam(1).TeamJam(1).ScoringTrip(1).Score", json!(0)),
+            ("ScoreBoard.Game(g1).Period(1).Jam(1).TeamJam(1).ScoringTrip(1).Duration", json!(6000)),
+            ("ScoreBoard.Game(g1).Period(1).Jam(1).TeamJam(1).ScoringTrip(2).Score", json!(4)),
+            ("ScoreBoard.Game(g1).Period(1).Jam(1).TeamJam(1).ScoringTrip(2).Duration", json!(12500)),
+            ("ScoreBoard.Game(g1).Period(1).Jam(1).TeamJam(1).ScoringTrip(3).Score", json!(3)),
+            ("ScoreBoard.Game(g1).Period(1).Jam(2).TeamJam(1).Fielding(Jammer).Skater", json!("h1")),
+            ("ScoreBoard.Game(g1).Period(1).Jam(2).TeamJam(1).ScoringTrip(1).Score", json!(0)),
+            ("ScoreBoard.Game(g1).Period(1).Jam(2).TeamJam(1).ScoringTrip(2).Score", json!(2)),
+        ]
+    }
+
+    fn get_jammer_trips(game: &Game) -> Vec<(String, usize, u64, f32)> {
+        ScoringTrips::get_game_stats(game).jammers.into_iter()
+            .map(|jammer| (jammer.name, jammer.trip_count, jammer.total_score, jammer.mean_points_per_trip))
+            .collect()
+    }
+
+    #[test]
+    fn excludes_initial_trip() {
+        let stats = ScoringTrips::get_game_stats(&get_game(&get_jam_entries()));
+
+        let trips: Vec<(u32, u64, Option<f32>)> = stats.jams[0].teams[0].trips.iter()
+            .map(|trip| (trip.trip_number, trip.score, trip.duration))
+            .collect();
+
+        assert_eq!(trips, vec![(2, 4, Some(12.5)), (3, 3, None)]);
+        assert_eq!(stats.jams[0].teams[0].trip_count, 2);
+        assert_eq!(stats.jams[1].teams[0].trip_count, 1);
+    }
+
+    #[test]
+    fn computes_points_per_trip_for_jammers_and_teams() {
+        let game = get_game(&get_jam_entries());
+        let stats = ScoringTrips::get_game_stats(&game);
+
+        assert_eq!(get_jammer_trips(&game), vec![
+            ("Home Jammer".to_string(), 3, 9, 3.0),
+        ]);
+
+        assert_eq!(stats.teams[0].trip_count, 3);
+        assert_eq!(stats.teams[0].total_score, 9);
+        assert_eq!(stats.teams[0].mean_trips_per_jam, 1.5);
+    }
+
+    #[test]
+    fn attributes_trips_after_star_pass_to_pivot() {
+        let mut entries = get_jam_entries();
+        entries.push(("ScoreBoard.Game(g1).Period(1).Jam(1).TeamJam(1).ScoringTrip(3).AfterSP", json!(true)));
+
+        assert_eq!(get_jammer_trips(&get_game(&entries)), vec![
+            ("Home Jammer".to_string(), 2, 6, 3.0),
+            ("Home Pivot".to_string(), 1, 3, 3.0),
+        ]);
+    }
+}
@@ -0,0 +1,71 @@
+use std::sync::{Arc, atomic::{AtomicUsize, AtomicU64, Ordering}};
+
+use serde_json::{json, Value};
+
+use crate::socket_server::UpdateProvider;
+
+#[derive(Clone, Default)]
+pub struct ServerDiagnostics {
+    counters: Arc<DiagnosticCounters>,
+}
+
+#[derive(Default)]
+struct DiagnosticCounters {
+    connected_clients: AtomicUsize,
+    updates_queued: AtomicU64,
+    updates_sent: AtomicU64,
+    updates_coalesced: AtomicU64,
+    clients_dropped: AtomicU64,
+    responses_dropped: AtomicU64,
+}
+
+impl ServerDiagnostics {
+    pub fn client_connected(&self) {
+        self.counters.connected_clients.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn client_disconnected(&self) {
+        self.counters.connected_clients.fetch_sub(1, Ordering::Relaxed);
+    }
+
+    pub fn update_queued(&self) {
+        self.counters.updates_queued.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn update_sent(&self) {
+        self.counters.updates_sent.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn update_coalesced(&self) {
+        self.counters.updates_coalesced.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn client_dropped(&self) {
+        self.counters.clients_dropped.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn response_dropped(&self) {
+        self.counters.responses_dropped.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn get_state_json(&self) -> Value {
+        json!({
+            "connectedClients": self.counters.connected_clients.load(Ordering::Relaxed),
+            "updatesQueued": self.counters.updates_queued.load(Ordering::Relaxed),
+            "updatesSent": self.counters.updates_sent.load(Ordering::Relaxed),
+            "updatesCoalesced": self.counters.updates_coalesced.load(Ordering::Relaxed),
+            "clientsDropped": self.counters.clients_dropped.load(Ordering::Relaxed),
+            "responsesDropped": self.counters.responses_dropped.load(Ordering::Relaxed),
+        })
+    }
+}
+
+impl UpdateProvider for ServerDiagnostics {
+    fn get_state(&self, _game_id: &str) -> Option<Value> {
+        Some(self.get_state_json())
+    }
+
+    fn get_empty_state(&self) -> Value {
+        self.get_state_json()
+    }
+}
@@ -0,0 +1,67 @@
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use log::{error, info};
+use serde::{Serialize, Deserialize};
+
+#[derive(Serialize, Deserialize)]
+pub struct RecordedMessage {
+    #[serde(rename = "receivedAt")]
+    pub received_at: u64,
+
+    #[serde(flatten)]
+    pub event: RecordedEvent,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+#[serde(tag = "type")]
+pub enum RecordedEvent {
+    Message { message: String },
+    Reset,
+}
+
+pub struct SessionRecorder {
+    path: String,
+    writer: BufWriter<File>,
+}
+
+impl SessionRecorder {
+    pub fn new(path: String) -> Result<SessionRecorder, String> {
+        let file = File::create(&path).map_err(|e| format!("Unable to create recording file {}: {:?}", path, e))?;
+
+        info!("Recording scoreboard session to {}", path);
+
+        Ok(SessionRecorder {
+            path,
+            writer: BufWriter::new(file),
+        })
+    }
+
+    pub fn record_message(&mut self, message: &str) {
+        self.record(RecordedEvent::Message { message: message.to_string() });
+    }
+
+    pub fn record_reset(&mut self) {
+        self.record(RecordedEvent::Reset);
+    }
+
+    fn record(&mut self, event: RecordedEvent) {
+        let received_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or_default();
+
+        let line = match serde_json::to_string(&RecordedMessage { received_at, event }) {
+            Ok(l) => l,
+            Err(e) => {
+                error!("Error serializing recorded message: {:?}", e);
+                return;
+            }
+        };
+
+        if let Err(e) = writeln!(self.writer, "{}", line).and_then(|_| self.writer.flush()) {
+            error!("Error writing to recording file {}: {:?}", self.path, e);
+        }
+    }
+}
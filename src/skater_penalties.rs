@@ -0,0 +1,232 @@
+use std::cmp::Ordering;
+
+use serde::{Serialize, Deserialize};
+use serde_json::{json, Value};
+
+use crate::{game_stats::GameStats, game_model::Game};
+
+const FOUL_OUT_PENALTY_ID: u32 = 0;
+const FOUL_OUT_CODE: &str = "FO";
+
+#[derive(Serialize, Deserialize, PartialEq)]
+struct PenaltyInfo {
+    #[serde(rename = "code")]
+    code: String,
+
+    #[serde(rename = "periodNumber")]
+    period_number: u8,
+
+    #[serde(rename = "jamNumber")]
+    jam_number: u32,
+}
+
+#[derive(Serialize, Deserialize, PartialEq)]
+struct SkaterPenaltyInfo {
+    #[serde(rename = "name")]
+    name: String,
+
+    #[serde(rename = "rosterNumber")]
+    roster_number: String,
+
+    #[serde(rename = "team")]
+    team: u8,
+
+    #[serde(rename = "penaltyCount")]
+    penalty_count: usize,
+
+    #[serde(rename = "penalties")]
+    penalties: Vec<PenaltyInfo>,
+
+    #[serde(rename = "atFivePenalties")]
+    at_five_penalties: bool,
+
+    #[serde(rename = "atSixPenalties")]
+    at_six_penalties: bool,
+
+    #[serde(rename = "fouledOut")]
+    fouled_out: bool,
+
+    #[serde(rename = "expulsion")]
+    expulsion: Option<PenaltyInfo>,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Default)]
+struct SkaterPenaltyStates {
+    #[serde(rename = "skaters")]
+    skaters: Vec<SkaterPenaltyInfo>,
+}
+
+pub struct SkaterPenalties;
+
+impl SkaterPenalties {
+    fn get_game_stats(game: &Game) -> SkaterPenaltyStates {
+        let mut skaters: Vec<SkaterPenaltyInfo> = game.teams.iter()
+            .flat_map(|(team, team_details)| team_details.skaters.values().map(move |skater| (*team, skater)))
+            .map(|(team, skater)| {
+                let (foul_outs, penalties): (Vec<_>, Vec<_>) = skater.penalties.iter()
+                    .filter(|(_, penalty)| !penalty.code.is_empty())
+                    .map(|(penalty_id, penalty)| (*penalty_id, PenaltyInfo {
+                        code: penalty.code.clone(),
+                        period_number: penalty.period_number,
+                        jam_number: penalty.jam_number,
+                    }))
+                    .partition(|(penalty_id, _)| *penalty_id == FOUL_OUT_PENALTY_ID);
+
+                let penalties: Vec<PenaltyInfo> = penalties.into_iter().map(|(_, penalty)| penalty).collect();
+                let foul_out = foul_outs.into_iter().map(|(_, penalty)| penalty).next();
+
+                let fouled_out = foul_out.as_ref().is_some_and(|penalty| penalty.code == FOUL_OUT_CODE);
+
+                SkaterPenaltyInfo {
+                    name: skater.name.clone(),
+                    roster_number: skater.roster_number.clone(),
+                    team,
+                    penalty_count: penalties.len(),
+                    at_five_penalties: penalties.len() == 5,
+                    at_six_penalties: penalties.len() >= 6,
+                    fouled_out,
+                    expulsion: foul_out.filter(|_| !fouled_out),
+                    penalties,
+                }
+            })
+            .filter(|stats| stats.penalty_count > 0 || stats.fouled_out || stats.expulsion.is_some())
+            .collect();
+
+        skaters.sort_by(|a, b| a.team.cmp(&b.team).then_with(|| Self::compare_roster_numbers(&a.roster_number, &b.roster_number)));
+
+        SkaterPenaltyStates { skaters }
+    }
+
+    fn compare_roster_numbers(a: &str, b: &str) -> Ordering {
+        match (a.parse::<u32>(), b.parse::<u32>()) {
+            (Ok(a_number), Ok(b_number)) => a_number.cmp(&b_number).then_with(|| a.cmp(b)),
+            (Ok(_), Err(_)) => Ordering::Less,
+            (Err(_), Ok(_)) => Ordering::Greater,
+            (Err(_), Err(_)) => a.cmp(b),
+        }
+    }
+}
+
+impl GameStats for SkaterPenalties {
+    const DATA_TYPE: &'static str = "SkaterPenalties";
+    const TOPICS: &'static [&'static str] = &[
+        "ScoreBoard.Game(*).Team(*).Skater(*).Name",
+        "ScoreBoard.Game(*).Team(*).Skater(*).RosterNumber",
+        "ScoreBoard.Game(*).Team(*).Skater(*).Penalty(*).Code",
+        "ScoreBoard.Game(*).Team(*).Skater(*).Penalty(*).PeriodNumber",
+        "ScoreBoard.Game(*).Team(*).Skater(*).Penalty(*).JamNumber",
+    ];
+
+    fn compute_game(game: &Game) -> Value {
+        json!(Self::get_game_stats(game))
+    }
+
+    fn get_empty_state() -> Value {
+        json!(SkaterPenaltyStates::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::{game_model::GameModel, scoreboard_path::ScoreboardPath};
+
+    fn get_game(entries: &[(String, Value)]) -> Game {
+        let mut model = GameModel::default();
+
+        for (key, value) in entries {
+            model.apply_change(&ScoreboardPath::parse(key).unwrap(), value).unwrap();
+        }
+
+        model.games.remove("g1").unwrap()
+    }
+
+    fn get_skater_entries(skater_id: &str, roster_number: &str, penalty_count: u32) -> Vec<(String, Value)> {
+        let skater_key = format!("ScoreBoard.Game(g1).Team(1).Skater({})", skater_id);
+
+        let mut entries = vec![
+            (format!("{}.Name", skater_key), json!(format!("Skater {}", roster_number))),
+            (format!("{}.RosterNumber", skater_key), json!(roster_number)),
+        ];
+
+        for penalty_id in 1..=penalty_count {
+            entries.extend([
+                (format!("{}.Penalty({}).Code", skater_key, penalty_id), json!("B")),
+                (format!("{}.Penalty({}).PeriodNumber", skater_key, penalty_id), json!(1)),
+                (format!("{}.Penalty({}).JamNumber", skater_key, penalty_id), json!(penalty_id)),
+            ]);
+        }
+
+        entries
+    }
+
+    fn get_foul_out_entries(skater_id: &str, code: &str) -> Vec<(String, Value)> {
+        let penalty_key = format!("ScoreBoard.Game(g1).Team(1).Skater({}).Penalty(0)", skater_id);
+
+        vec![
+            (format!("{}.Code", penalty_key), json!(code)),
+            (format!("{}.PeriodNumber", penalty_key), json!(2)),
+            (format!("{}.JamNumber", penalty_key), json!(7)),
+        ]
+    }
+
+    fn get_skater<'a>(stats: &'a SkaterPenaltyStates, roster_number: &str) -> &'a SkaterPenaltyInfo {
+        stats.skaters.iter().find(|skater| skater.roster_number == roster_number).unwrap()
+    }
+
+    #[test]
+    fn flags_five_and_six_penalties() {
+        let mut entries = get_skater_entries("s1", "1", 4);
+        entries.extend(get_skater_entries("s2", "2", 5));
+        entries.extend(get_skater_entries("s3", "3", 6));
+
+        let stats = SkaterPenalties::get_game_stats(&get_game(&entries));
+
+        let flags: Vec<(usize, bool, bool)> = stats.skaters.iter()
+            .map(|skater| (skater.penalty_count, skater.at_five_penalties, skater.at_six_penalties))
+            .collect();
+
+        assert_eq!(flags, vec![(4, false, false), (5, true, false), (6, false, true)]);
+    }
+
+    #[test]
+    fn separates_foul_outs_from_expulsions() {
+        let mut entries = get_skater_entries("s1", "1", 6);
+        entries.extend(get_foul_out_entries("s1", "FO"));
+        entries.extend(get_skater_entries("s2", "2", 1));
+        entries.extend(get_foul_out_entries("s2", "B"));
+
+        let stats = SkaterPenalties::get_game_stats(&get_game(&entries));
+
+        let fouled_out = get_skater(&stats, "1");
+        assert!(fouled_out.fouled_out);
+        assert!(fouled_out.expulsion.is_none());
+        assert_eq!(fouled_out.penalty_count, 6);
+
+        let expelled = get_skater(&stats, "2");
+        assert!(!expelled.fouled_out);
+        assert_eq!(expelled.penalty_count, 1);
+
+        let expulsion = expelled.expulsion.as_ref().unwrap();
+        assert_eq!((expulsion.code.as_str(), expulsion.period_number, expulsion.jam_number), ("B", 2, 7));
+    }
+
+    #[test]
+    fn sorts_roster_numbers_numerically() {
+        let mut entries = Vec::new();
+
+        for (skater_id, roster_number) in [("s1", "10"), ("s2", "2"), ("s3", "A1"), ("s4", "007"), ("s5", "99")] {
+            entries.extend(get_skater_entries(skater_id, roster_number, 1));
+        }
+
+        let stats = SkaterPenalties::get_game_stats(&get_game(&entries));
+
+        let roster_numbers: Vec<&str> = stats.skaters.iter()
+            .map(|skater| skater.roster_number.as_str())
+            .collect();
+
+        assert_eq!(roster_numbers, vec!["2", "007", "10", "99", "A1"]);
+    }
+}
@@ -1,44 +1,101 @@
-use std::{sync::{Arc, atomic::{AtomicUsize, Ordering}}, collections::HashMap};
+use std::{sync::{Arc, atomic::{AtomicUsize, Ordering}}, collections::HashMap, convert::Infallible, time::Duration};
 
 use futures_util::{SinkExt, StreamExt};
-use log::{error, trace, debug};
+use log::{error, warn, trace, debug};
 use serde::{Serialize, Deserialize};
 use serde_json::{Value, json};
 use tokio::sync::{mpsc, RwLock, Mutex};
-use tokio_stream::wrappers::UnboundedReceiverStream;
-use warp::Filter;
+use tokio_stream::wrappers::ReceiverStream;
+use warp::{Filter, Reply, http::StatusCode};
 use warp::ws::{WebSocket, Message};
 
-type Connections = Arc<RwLock<HashMap<usize, mpsc::UnboundedSender<Update>>>>;
+use crate::{client_queue::{ClientQueue, QueuedMessage}, server_diagnostics::ServerDiagnostics};
+
+pub const PROTOCOL_VERSION: u32 = 1;
+
+const UPDATE_CHANNEL_CAPACITY: usize = 1000;
+const SUBSCRIBE_CHANNEL_CAPACITY: usize = 100;
+const STALLED_CLIENT_TIMEOUT: Duration = Duration::from_secs(30);
+
+type Connections = Arc<RwLock<HashMap<usize, Arc<ClientQueue>>>>;
 type Subscribers = RwLock<Vec<usize>>;
 type SubscribeChannel = (SubscribeSender, SubscribeReceiver);
-type SubscribeSender = mpsc::UnboundedSender<(usize, SubscribeMessage)>;
-type SubscribeReceiver = mpsc::UnboundedReceiver<(usize, SubscribeMessage)>;
-type SubscriptionKey = (String, String);
+type SubscribeSender = mpsc::Sender<(usize, Option<Value>, SubscriptionRequest)>;
+type SubscribeReceiver = mpsc::Receiver<(usize, Option<Value>, SubscriptionRequest)>;
+pub type SubscriptionKey = (String, String);
 type Subscriptions = Arc<RwLock<HashMap<SubscriptionKey, Subscribers>>>;
-type UpdateChannel = (UpdateSender, UpdateReceiver);
-pub type UpdateSender = mpsc::UnboundedSender<Update>;
-type UpdateReceiver = mpsc::UnboundedReceiver<Update>;
+pub type UpdateSender = mpsc::Sender<Update>;
+type UpdateReceiver = mpsc::Receiver<Update>;
 type UpdateProviders = Arc<RwLock<HashMap<String, Arc<Mutex<dyn UpdateProvider + Send>>>>>;
 
 pub trait UpdateProvider {
-    fn get_state(&self, game_id: &String) -> Value;
+    fn get_state(&self, game_id: &str) -> Option<Value>;
+    fn get_empty_state(&self) -> Value;
 }
 
 #[derive(Serialize, Deserialize)]
 struct GenericMessage {
     #[serde(rename = "messageType")]
     pub message_type: String,
+    #[serde(rename = "protocolVersion")]
+    pub protocol_version: Option<u32>,
+    #[serde(rename = "requestId")]
+    pub request_id: Option<Value>,
 }
 
 #[derive(Serialize, Deserialize)]
-struct SubscribeMessage {
+struct SubscriptionMessage {
     #[serde(rename = "messageType")]
     pub message_type: String,
+    #[serde(rename = "dataType")]
+    pub data_type: Option<String>,
+    #[serde(rename = "gameId")]
+    pub game_id: Option<String>,
+    #[serde(rename = "updateMode")]
+    pub update_mode: Option<UpdateMode>,
+    #[serde(rename = "subscriptions", default)]
+    pub subscriptions: Vec<Subscription>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Subscription {
     #[serde(rename = "dataType")]
     pub data_type: String,
     #[serde(rename = "gameId")]
     pub game_id: String,
+    #[serde(rename = "updateMode")]
+    pub update_mode: Option<UpdateMode>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum UpdateMode {
+    #[serde(rename = "full")]
+    #[default]
+    Full,
+    #[serde(rename = "patch")]
+    Patch,
+}
+
+enum SubscriptionRequest {
+    Subscribe(Vec<(SubscriptionKey, UpdateMode)>),
+    Unsubscribe(Vec<SubscriptionKey>),
+    UnsubscribeAll,
+    ListDataTypes,
+}
+
+impl SubscriptionMessage {
+    fn get_subscriptions(self) -> Vec<(SubscriptionKey, UpdateMode)> {
+        let default_update_mode = self.update_mode.unwrap_or_default();
+
+        let single_subscription = match (self.game_id, self.data_type) {
+            (Some(game_id), Some(data_type)) => Some(((game_id, data_type), default_update_mode)),
+            _ => None,
+        };
+
+        single_subscription.into_iter()
+            .chain(self.subscriptions.into_iter().map(|s| ((s.game_id, s.data_type), s.update_mode.unwrap_or(default_update_mode))))
+            .collect()
+    }
 }
 
 #[derive(Clone)]
@@ -48,23 +105,59 @@ pub struct Update {
     pub update: Value
 }
 
+struct EventSubscription {
+    connection_id: usize,
+    subscription_key: SubscriptionKey,
+    connections: Connections,
+    subscriptions: Subscriptions,
+    queue: Arc<ClientQueue>,
+    diagnostics: ServerDiagnostics,
+}
+
+impl Drop for EventSubscription {
+    fn drop(&mut self) {
+        debug!("Event stream client {} disconnected", self.connection_id);
+
+        let connection_id = self.connection_id;
+        let subscription_key = self.subscription_key.clone();
+        let connections = self.connections.clone();
+        let subscriptions = self.subscriptions.clone();
+        let queue = self.queue.clone();
+
+        self.diagnostics.client_disconnected();
+
+        tokio::task::spawn(async move {
+            connections.write().await.remove(&connection_id);
+            SocketServer::unsubscribe(connection_id, &subscription_key, &subscriptions, &queue).await;
+            queue.close();
+        });
+    }
+}
+
 static NEXT_CONNECTION_ID: AtomicUsize = AtomicUsize::new(0);
 
 pub struct SocketServer {
     update_sender: UpdateSender,
     update_receiver: UpdateReceiver,
     update_providers: UpdateProviders,
+    diagnostics: ServerDiagnostics,
 }
 
 impl SocketServer {
 
     pub fn new() -> SocketServer {
-        let (update_sender, update_receiver) = mpsc::unbounded_channel();
+        let (update_sender, update_receiver) = mpsc::channel(UPDATE_CHANNEL_CAPACITY);
+
+        let diagnostics = ServerDiagnostics::default();
+
+        let mut update_providers: HashMap<String, Arc<Mutex<dyn UpdateProvider + Send>>> = HashMap::new();
+        update_providers.insert("ServerDiagnostics".to_string(), Arc::new(Mutex::new(diagnostics.clone())));
 
         SocketServer { 
             update_sender,
             update_receiver,
-            update_providers: Arc::new(RwLock::new(HashMap::new())),
+            update_providers: Arc::new(RwLock::new(update_providers)),
+            diagnostics,
         }
     }
 
@@ -73,8 +166,8 @@ impl SocketServer {
         self.update_sender.clone()
     }
 
-    pub async fn register_update_provider(&mut self, data_type: &String, update_provider: Arc<Mutex<dyn UpdateProvider + Send>>) {
-        self.update_providers.write().await.insert(data_type.clone(), update_provider);
+    pub async fn register_update_provider(&mut self, data_type: &str, update_provider: Arc<Mutex<dyn UpdateProvider + Send>>) {
+        self.update_providers.write().await.insert(data_type.to_string(), update_provider);
     }
 
     pub async fn listen(mut self, port: u16) {
@@ -87,35 +180,42 @@ impl SocketServer {
         tokio::task::spawn(async move {
             debug!("Starting update receiver thread");
             while let Some(update) = self.update_receiver.recv().await {
-                let key = (update.game_id.clone(), update.data_type.clone());
-
-                trace!("Update receiver forwarding update of type {}", update.data_type.clone());
-
-                if let Some(subscription) = thread_subscriptions.read().await.get(&key) {
-                    for subscriber in subscription.read().await.clone().into_iter() {
-                        if let Some(subscriber) = thread_connections.read().await.get(&subscriber) {
-                            trace!("Sending update from update receiver thread");
-                            if let Err(e) = subscriber.send(update.clone()) {
-                                error!("Error sending update across mpsc: {:?}", e);
-                            }
-                        }
-                    }
-                }
+                Self::forward_update(update, &thread_subscriptions, &thread_connections).await;
             }
         });
 
         let connections = warp::any().map(move || connections.clone());
         let subscriptions = warp::any().map(move || subscriptions.clone());
         let update_providers = warp::any().map(move || self.update_providers.clone());
+        let diagnostics = warp::any().map(move || self.diagnostics.clone());
 
         let websocket_path = warp::path("ws")
             .and(warp::ws())
+            .and(connections.clone())
+            .and(subscriptions.clone())
+            .and(update_providers.clone())
+            .and(diagnostics.clone())
+            .map(|ws: warp::ws::Ws, connections, subscriptions, update_providers, diagnostics| {
+                ws.on_upgrade(move |websocket| Self::socket_connected(websocket, connections, subscriptions, update_providers, diagnostics))
+            });
+
+        let games_path = warp::path!("api" / "games")
+            .and(warp::get())
+            .and(update_providers.clone())
+            .and_then(Self::get_games);
+
+        let game_state_path = warp::path!("api" / "games" / String / String)
+            .and(warp::get())
+            .and(update_providers.clone())
+            .and_then(Self::get_game_state);
+
+        let game_events_path = warp::path!("api" / "games" / String / String / "events")
+            .and(warp::get())
             .and(connections)
             .and(subscriptions)
-            .and(update_providers)
-            .map(|ws: warp::ws::Ws, connections, subscriptions, update_providers| {
-                ws.on_upgrade(move |websocket| Self::socket_connected(websocket, connections, subscriptions, update_providers))
-            });
+            .and(update_providers.clone())
+            .and(diagnostics)
+            .and_then(Self::get_game_events);
 
         let ui_path = std::env::current_exe().unwrap().parent().unwrap().join("ui");
 
@@ -128,93 +228,372 @@ impl SocketServer {
                 .allow_any_origin()
                 .allow_methods(vec!["GET", "OPTIONS"]);
 
-        let routes = websocket_path.or(default_path).or(ui_files).with(cors_configuration);
+        let routes = websocket_path
+            .or(games_path)
+            .or(game_state_path)
+            .or(game_events_path)
+            .or(default_path)
+            .or(ui_files)
+            .with(cors_configuration);
 
         warp::serve(routes).run(([0, 0, 0, 0], port)).await;
     }
 
-    async fn socket_connected(websocket: WebSocket, connections: Connections, subscriptions: Subscriptions, update_providers: UpdateProviders) {
-        let connection_id = NEXT_CONNECTION_ID.fetch_add(1, Ordering::Relaxed);
+    async fn forward_update(update: Update, subscriptions: &Subscriptions, connections: &Connections) {
+        let key = (update.game_id.clone(), update.data_type.clone());
 
-        debug!("New client connected and assigned ID: {}", connection_id);
+        trace!("Update receiver forwarding update of type {}", update.data_type.clone());
 
-        let (mut client_sender, mut client_receiver) = websocket.split();
+        if let Some(subscription) = subscriptions.read().await.get(&key) {
+            for subscriber in subscription.read().await.clone().into_iter() {
+                if let Some(subscriber) = connections.read().await.get(&subscriber) {
+                    trace!("Queueing update from update receiver thread");
+                    subscriber.push(update.clone());
+                }
+            }
+        }
+    }
 
-        let (subscribe_sender, subscribe_receiver): SubscribeChannel = mpsc::unbounded_channel();
-        let mut subscribe_receiver = UnboundedReceiverStream::new(subscribe_receiver);
+    async fn get_games(update_providers: UpdateProviders) -> Result<impl warp::Reply, warp::Rejection> {
+        let provider = update_providers.read().await.get("Games").cloned();
 
-        let thread_connections = connections.clone();
+        let games = match provider {
+            Some(p) => Self::get_provider_state(&p, "*").await,
+            None => json!([]),
+        };
 
-        tokio::task::spawn(async move {
-            while let Some((connection_id, subscribe_request)) = subscribe_receiver.next().await {
-                debug!("Processing subscription request on {} for connection {}", subscribe_request.data_type.clone(), connection_id);
+        Ok(warp::reply::json(&games))
+    }
 
-                let mut subscriptions = subscriptions.write().await;
-                let subscription_key: SubscriptionKey = (subscribe_request.game_id.clone(), subscribe_request.data_type.clone());
+    async fn get_game_state(game_id: String, data_type: String, update_providers: UpdateProviders) -> Result<impl warp::Reply, warp::Rejection> {
+        debug!("REST request for {} on game {}", data_type, game_id);
 
-                if !subscriptions.contains_key(&subscription_key) {
-                    subscriptions.insert(subscription_key.clone(), Subscribers::default());
-                }
+        let provider = match Self::validate_subscription(&(game_id.clone(), data_type), &update_providers).await {
+            Ok(p) => p,
+            Err(e) => return Ok(Self::get_not_found_reply(&e)),
+        };
 
-                let mut subscribers = subscriptions.get(&subscription_key).unwrap().write().await;
+        if game_id != "*" && !Self::game_exists(&update_providers, &game_id).await {
+            return Ok(Self::get_not_found_reply(&format!("Unknown game '{}'", game_id)));
+        }
 
-                if !subscribers.contains(&connection_id) {
-                    subscribers.push(connection_id.clone())
-                }
+        let state = Self::get_provider_state(&provider, &game_id).await;
 
-                if let Some(provider) = update_providers.read().await.get(&subscribe_request.data_type) {
-                    if let Some(connection) = thread_connections.read().await.get(&connection_id) {
-                        let state = provider.lock().await.get_state(&subscribe_request.game_id);
-                        if let Err(e) = connection.send(Update {
-                            game_id: subscribe_request.game_id.clone(),
-                            data_type: subscribe_request.data_type.clone(),
-                            update: state,
-                        }) {
-                            error!("Error sending initial state to connection {}: {:?}", connection_id, e);
-                        };
-                    }
-                }
-            }
+        Ok(warp::reply::with_status(warp::reply::json(&state), StatusCode::OK))
+    }
+
+    async fn get_game_events(game_id: String, data_type: String, connections: Connections, subscriptions: Subscriptions, update_providers: UpdateProviders, diagnostics: ServerDiagnostics) -> Result<warp::reply::Response, warp::Rejection> {
+        let subscription_key = (game_id, data_type);
+
+        if let Err(e) = Self::validate_subscription(&subscription_key, &update_providers).await {
+            return Ok(Self::get_not_found_reply(&e).into_response());
+        }
+
+        let connection_id = NEXT_CONNECTION_ID.fetch_add(1, Ordering::Relaxed);
+
+        debug!("New event stream client for {} on game {} assigned ID: {}", subscription_key.1, subscription_key.0, connection_id);
+
+        let queue = Arc::new(ClientQueue::new(diagnostics.clone()));
+
+        connections.write().await.insert(connection_id, queue.clone());
+        diagnostics.client_connected();
+
+        Self::subscribe(connection_id, subscription_key.clone(), UpdateMode::Full, &subscriptions, &queue, &update_providers).await;
+
+        let event_subscription = EventSubscription {
+            connection_id,
+            subscription_key,
+            connections,
+            subscriptions,
+            queue: queue.clone(),
+            diagnostics,
+        };
+
+        let events = futures::stream::unfold(event_subscription, |event_subscription| async move {
+            let message = event_subscription.queue.next().await?;
+            let event = warp::sse::Event::default()
+                .event("update")
+                .data(Self::get_message_json(message).to_string());
+
+            event_subscription.diagnostics.update_sent();
+
+            Some((Ok::<_, Infallible>(event), event_subscription))
         });
 
-        let (update_sender, update_receiver): UpdateChannel = mpsc::unbounded_channel();
-        let mut update_receiver = UnboundedReceiverStream::new(update_receiver);
+        Ok(warp::sse::reply(warp::sse::keep_alive().stream(events)).into_response())
+    }
+
+    async fn validate_subscription(subscription_key: &SubscriptionKey, update_providers: &UpdateProviders) -> Result<Arc<Mutex<dyn UpdateProvider + Send>>, String> {
+        let (_, data_type) = subscription_key;
+
+        update_providers.read().await.get(data_type).cloned()
+            .ok_or(format!("Unknown data type '{}'", data_type))
+    }
+
+    async fn game_exists(update_providers: &UpdateProviders, game_id: &str) -> bool {
+        let provider = update_providers.read().await.get("Games").cloned();
+
+        let games = match provider {
+            Some(p) => Self::get_provider_state(&p, "*").await,
+            None => return false,
+        };
+
+        games.as_array()
+            .map(|games| games.iter().any(|game| game["id"].as_str() == Some(game_id)))
+            .unwrap_or(false)
+    }
+
+    async fn get_provider_state(provider: &Arc<Mutex<dyn UpdateProvider + Send>>, game_id: &str) -> Value {
+        let provider = provider.lock().await;
+
+        provider.get_state(game_id).unwrap_or_else(|| provider.get_empty_state())
+    }
+
+    fn get_not_found_reply(error: &str) -> warp::reply::WithStatus<warp::reply::Json> {
+        warp::reply::with_status(warp::reply::json(&json!({ "error": error })), StatusCode::NOT_FOUND)
+    }
+
+    async fn socket_connected(websocket: WebSocket, connections: Connections, subscriptions: Subscriptions, update_providers: UpdateProviders, diagnostics: ServerDiagnostics) {
+        let connection_id = NEXT_CONNECTION_ID.fetch_add(1, Ordering::Relaxed);
+
+        debug!("New client connected and assigned ID: {}", connection_id);
+
+        let (mut client_sender, mut client_receiver) = websocket.split();
+
+        let (subscribe_sender, subscribe_receiver): SubscribeChannel = mpsc::channel(SUBSCRIBE_CHANNEL_CAPACITY);
+        let mut subscribe_receiver = ReceiverStream::new(subscribe_receiver);
+
+        let queue = Arc::new(ClientQueue::new(diagnostics.clone()));
+        let thread_queue = queue.clone();
 
         tokio::task::spawn(async move {
-            while let Some(update) = update_receiver.next().await {
-                debug!("Update received for {}. Sending to {}", update.data_type.clone(), connection_id.clone());
+            while let Some((connection_id, request_id, request)) = subscribe_receiver.next().await {
+                match request {
+                    SubscriptionRequest::Subscribe(requested_subscriptions) => {
+                        Self::handle_subscribe(connection_id, request_id.as_ref(), requested_subscriptions, &subscriptions, &thread_queue, &update_providers).await;
+                    },
+                    SubscriptionRequest::Unsubscribe(keys) => {
+                        for subscription_key in keys.iter() {
+                            Self::unsubscribe(connection_id, subscription_key, &subscriptions, &thread_queue).await;
+                        }
 
-                if let Err(e) = client_sender.send(Message::text(Self::get_update_json(&update).to_string())).await {
-                    error!("Error sending update to client {}: {:?}", connection_id, e);
+                        let unsubscribed: Vec<Value> = keys.into_iter()
+                            .map(|(game_id, data_type)| json!({ "gameId": game_id, "dataType": data_type }))
+                            .collect();
+
+                        thread_queue.push_response(Self::get_response_json("Unsubscribed", request_id.as_ref(), json!({ "subscriptions": unsubscribed })));
+                    },
+                    SubscriptionRequest::UnsubscribeAll => {
+                        Self::unsubscribe_all(connection_id, &subscriptions, &thread_queue).await;
+                        thread_queue.push_response(Self::get_response_json("Unsubscribed", request_id.as_ref(), json!({ "subscriptions": [] })));
+                    },
+                    SubscriptionRequest::ListDataTypes => {
+                        let mut data_types: Vec<String> = update_providers.read().await.keys().cloned().collect();
+                        data_types.sort();
+
+                        thread_queue.push_response(Self::get_response_json("DataTypes", request_id.as_ref(), json!({ "dataTypes": data_types })));
+                    },
                 }
             }
         });
 
-        connections.write().await.insert(connection_id, update_sender);
+        let thread_queue = queue.clone();
+        let thread_diagnostics = diagnostics.clone();
+
+        let mut send_task = tokio::task::spawn(async move {
+            while let Some(queued_message) = thread_queue.next().await {
+                debug!("Sending queued message to {}", connection_id);
+
+                let message = Message::text(Self::get_message_json(queued_message).to_string());
+
+                match tokio::time::timeout(STALLED_CLIENT_TIMEOUT, client_sender.send(message)).await {
+                    Ok(Ok(_)) => thread_diagnostics.update_sent(),
+                    Ok(Err(e)) => {
+                        error!("Error sending update to client {}: {:?}", connection_id, e);
+                        break;
+                    },
+                    Err(_) => {
+                        warn!("Client {} stalled for more than {} seconds. Dropping connection", connection_id, STALLED_CLIENT_TIMEOUT.as_secs());
+                        thread_diagnostics.client_dropped();
+                        break;
+                    }
+                }
+            }
+
+            debug!("Stopped sending updates to connection {}", connection_id);
+        });
+
+        connections.write().await.insert(connection_id, queue.clone());
+        diagnostics.client_connected();
+
+        loop {
+            let result = tokio::select! {
+                result = client_receiver.next() => result,
+                _ = &mut send_task => break,
+            };
 
-        while let Some(result) = client_receiver.next().await {
             let message = match result {
-                Ok(message) => message,
-                Err(e) => {
+                Some(Ok(message)) => message,
+                Some(Err(e)) => {
                     error!("Error receiving data from websocket id {}: {:?}", connection_id, e);
                     break;
-                }
+                },
+                None => break,
             };
 
-            Self::handle_socket_message(connection_id, message, &subscribe_sender).await;
+            Self::handle_socket_message(connection_id, message, &subscribe_sender, &queue).await;
         }
 
         debug!("Connection {} disconnected", connection_id);
+
+        connections.write().await.remove(&connection_id);
+        queue.close();
+        diagnostics.client_disconnected();
+
+        if let Err(e) = subscribe_sender.send((connection_id, None, SubscriptionRequest::UnsubscribeAll)).await {
+            error!("Error sending subscription request on mpsc: {:?}", e);
+        }
+    }
+
+    async fn handle_subscribe(connection_id: usize, request_id: Option<&Value>, requested_subscriptions: Vec<(SubscriptionKey, UpdateMode)>, subscriptions: &Subscriptions, queue: &ClientQueue, update_providers: &UpdateProviders) {
+        let mut known_subscriptions = Vec::new();
+
+        for (subscription_key, update_mode) in requested_subscriptions {
+            match Self::validate_subscription(&subscription_key, update_providers).await {
+                Ok(_) => known_subscriptions.push((subscription_key, update_mode)),
+                Err(e) => queue.push_response(Self::get_error_json(request_id, &e)),
+            }
+        }
+
+        if known_subscriptions.is_empty() {
+            return;
+        }
+
+        let subscribed: Vec<Value> = known_subscriptions.iter()
+            .map(|((game_id, data_type), update_mode)| json!({ "gameId": game_id, "dataType": data_type, "updateMode": update_mode }))
+            .collect();
+
+        queue.push_response(Self::get_response_json("Subscribed", request_id, json!({ "subscriptions": subscribed })));
+
+        for (subscription_key, update_mode) in known_subscriptions {
+            Self::subscribe(connection_id, subscription_key, update_mode, subscriptions, queue, update_providers).await;
+        }
     }
 
-    fn get_update_json(update: &Update) -> Value {
-        json!({
-            "dataType": update.data_type,
-            "body": update.update,
-        })
+    async fn subscribe(connection_id: usize, subscription_key: SubscriptionKey, update_mode: UpdateMode, subscriptions: &Subscriptions, connection: &ClientQueue, update_providers: &UpdateProviders) {
+        let (game_id, data_type) = subscription_key.clone();
+
+        debug!("Processing subscription request on {} for connection {}", data_type, connection_id);
+
+        connection.set_update_mode(&subscription_key, update_mode);
+
+        {
+            let mut subscriptions = subscriptions.write().await;
+            let mut subscribers = subscriptions.entry(subscription_key).or_default().write().await;
+
+            if !subscribers.contains(&connection_id) {
+                subscribers.push(connection_id)
+            }
+        }
+
+        let provider = update_providers.read().await.get(&data_type).cloned();
+
+        if let Some(provider) = provider {
+            let state = Self::get_provider_state(&provider, &game_id).await;
+
+            connection.push(Update {
+                game_id,
+                data_type,
+                update: state,
+            });
+        }
     }
 
-    async fn handle_socket_message(connection_id: usize, message: Message, subscribe_sender: &mpsc::UnboundedSender<(usize, SubscribeMessage)>) {
+    async fn unsubscribe(connection_id: usize, subscription_key: &SubscriptionKey, subscriptions: &Subscriptions, connection: &ClientQueue) {
+        debug!("Processing unsubscribe request on {} for connection {}", subscription_key.1, connection_id);
+
+        connection.set_update_mode(subscription_key, UpdateMode::Full);
+
+        let mut subscriptions = subscriptions.write().await;
+
+        let is_empty = match subscriptions.get(subscription_key) {
+            Some(subscribers) => {
+                let mut subscribers = subscribers.write().await;
+                subscribers.retain(|id| *id != connection_id);
+                subscribers.is_empty()
+            },
+            None => return,
+        };
+
+        if is_empty {
+            subscriptions.remove(subscription_key);
+        }
+    }
+
+    async fn unsubscribe_all(connection_id: usize, subscriptions: &Subscriptions, connection: &ClientQueue) {
+        debug!("Removing all subscriptions for connection {}", connection_id);
+
+        connection.clear_update_modes();
+
+        let mut subscriptions = subscriptions.write().await;
+        let mut empty_keys = Vec::new();
+
+        for (subscription_key, subscribers) in subscriptions.iter() {
+            let mut subscribers = subscribers.write().await;
+            subscribers.retain(|id| *id != connection_id);
+
+            if subscribers.is_empty() {
+                empty_keys.push(subscription_key.clone());
+            }
+        }
+
+        for subscription_key in empty_keys {
+            subscriptions.remove(&subscription_key);
+        }
+    }
+
+    fn get_message_json(message: QueuedMessage) -> Value {
+        match message {
+            QueuedMessage::Update(update) => json!({
+                "messageType": "Update",
+                "protocolVersion": PROTOCOL_VERSION,
+                "gameId": update.game_id,
+                "dataType": update.data_type,
+                "body": update.update,
+            }),
+            QueuedMessage::Patch(update) => json!({
+                "messageType": "Update",
+                "protocolVersion": PROTOCOL_VERSION,
+                "gameId": update.game_id,
+                "dataType": update.data_type,
+                "patch": update.update,
+            }),
+            QueuedMessage::Response(response) => response,
+        }
+    }
+
+    fn get_response_json(message_type: &str, request_id: Option<&Value>, fields: Value) -> Value {
+        let mut response = json!({
+            "messageType": message_type,
+            "protocolVersion": PROTOCOL_VERSION,
+        });
+
+        if let Some(request_id) = request_id {
+            response["requestId"] = request_id.clone();
+        }
+
+        if let (Value::Object(response), Value::Object(fields)) = (&mut response, fields) {
+            response.extend(fields);
+        }
+
+        response
+    }
+
+    fn get_error_json(request_id: Option<&Value>, error: &str) -> Value {
+        Self::get_response_json("Error", request_id, json!({ "error": error }))
+    }
+
+    async fn handle_socket_message(connection_id: usize, message: Message, subscribe_sender: &SubscribeSender, queue: &ClientQueue) {
         let message_text = if let Ok(s) = message.to_str() {
             s
         } else {
@@ -223,30 +602,161 @@ impl SocketServer {
 
         trace!("Received message: {}", message_text);
 
-        let generic_message: GenericMessage = 
-            if let Ok(m) = serde_json::from_str(message_text) {
-                m
-            } else { 
-                return; 
-            };
+        let generic_message: GenericMessage = match serde_json::from_str(message_text) {
+            Ok(m) => m,
+            Err(e) => {
+                debug!("Invalid message received from connection {}: {:?}", connection_id, e);
+                queue.push_response(Self::get_error_json(None, &format!("Invalid message: {}", e)));
+                return;
+            }
+        };
+
+        let request_id = generic_message.request_id;
+
+        if let Some(version) = generic_message.protocol_version {
+            if version > PROTOCOL_VERSION {
+                queue.push_response(Self::get_error_json(request_id.as_ref(), &format!("Unsupported protocol version {}. Latest supported version is {}", version, PROTOCOL_VERSION)));
+                return;
+            }
+        }
 
-        match generic_message.message_type.as_str() {
-            "Subscribe" => {
-                let subscribe_message: SubscribeMessage = match serde_json::from_str(message_text) {
+        let request = match generic_message.message_type.as_str() {
+            "Subscribe" | "Unsubscribe" => {
+                let subscription_message: SubscriptionMessage = match serde_json::from_str(message_text) {
                     Ok(v) => { v }
                     Err(e) => { 
-                        error!("Failed to parse Subscribe message: {:?}", e);
+                        queue.push_response(Self::get_error_json(request_id.as_ref(), &format!("Invalid {} message: {}", generic_message.message_type, e)));
                         return; 
                     }
                 };
-                if let Err(e) = subscribe_sender.send((connection_id, subscribe_message)) {
-                    error!("Error sending subscribe request on mpsc: {:?}", e);
+
+                let requested_subscriptions = subscription_message.get_subscriptions();
+
+                if requested_subscriptions.is_empty() {
+                    queue.push_response(Self::get_error_json(request_id.as_ref(), &format!("{} message contained no subscriptions", generic_message.message_type)));
+                    return;
+                }
+
+                if generic_message.message_type == "Subscribe" {
+                    SubscriptionRequest::Subscribe(requested_subscriptions)
+                } else {
+                    SubscriptionRequest::Unsubscribe(requested_subscriptions.into_iter().map(|(key, _)| key).collect())
                 }
             },
-            _ => {
+            "UnsubscribeAll" => SubscriptionRequest::UnsubscribeAll,
+            "ListDataTypes" => SubscriptionRequest::ListDataTypes,
+            message_type => {
+                queue.push_response(Self::get_error_json(request_id.as_ref(), &format!("Unknown message type '{}'", message_type)));
                 return;
             }
+        };
+
+        if let Err(e) = subscribe_sender.send((connection_id, request_id, request)).await {
+            error!("Error sending subscription request on mpsc: {:?}", e);
+        }
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct TestProvider {
+        states: HashMap<String, Value>,
+    }
+
+    impl UpdateProvider for TestProvider {
+        fn get_state(&self, game_id: &str) -> Option<Value> {
+            self.states.get(game_id).cloned()
+        }
+
+        fn get_empty_state(&self) -> Value {
+            json!({ "jams": [] })
+        }
+    }
+
+    fn get_update_providers() -> UpdateProviders {
+        let mut update_providers: HashMap<String, Arc<Mutex<dyn UpdateProvider + Send>>> = HashMap::new();
+        update_providers.insert("Games".to_string(), Arc::new(Mutex::new(TestProvider { states: HashMap::from([("*".to_string(), json!([]))]) })));
+        update_providers.insert("TestStats".to_string(), Arc::new(Mutex::new(TestProvider { states: HashMap::new() })));
+
+        Arc::new(RwLock::new(update_providers))
+    }
+
+    async fn get_queued_messages(queue: &ClientQueue) -> Vec<Value> {
+        let mut messages = Vec::new();
+
+        while let Ok(Some(message)) = tokio::time::timeout(Duration::from_millis(50), queue.next()).await {
+            messages.push(SocketServer::get_message_json(message));
         }
+
+        messages
+    }
+
+    fn get_subscription_key(game_id: &str, data_type: &str) -> SubscriptionKey {
+        (game_id.to_string(), data_type.to_string())
     }
 
-}
\ No newline at end of file
+    #[tokio::test]
+    async fn subscribe_before_data_returns_empty_state() {
+        let update_providers = get_update_providers();
+        let subscriptions = Subscriptions::default();
+        let queue = ClientQueue::new(ServerDiagnostics::default());
+        let key = get_subscription_key("g1", "TestStats");
+
+        SocketServer::handle_subscribe(1, Some(&json!(7)), vec![(key.clone(), UpdateMode::Full)], &subscriptions, &queue, &update_providers).await;
+
+        let messages = get_queued_messages(&queue).await;
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[0]["messageType"], "Subscribed");
+        assert_eq!(messages[0]["requestId"], 7);
+        assert_eq!(messages[1]["messageType"], "Update");
+        assert_eq!(messages[1]["gameId"], "g1");
+        assert_eq!(messages[1]["body"], json!({ "jams": [] }));
+
+        assert_eq!(*subscriptions.read().await[&key].read().await, vec![1]);
+    }
+
+    #[tokio::test]
+    async fn subscribe_before_data_receives_later_updates() {
+        let update_providers = get_update_providers();
+        let subscriptions = Subscriptions::default();
+        let connections = Connections::default();
+        let queue = Arc::new(ClientQueue::new(ServerDiagnostics::default()));
+        let key = get_subscription_key("g1", "TestStats");
+
+        connections.write().await.insert(1, queue.clone());
+
+        SocketServer::handle_subscribe(1, None, vec![(key, UpdateMode::Full)], &subscriptions, &queue, &update_providers).await;
+        get_queued_messages(&queue).await;
+
+        let update = Update { game_id: "g1".to_string(), data_type: "TestStats".to_string(), update: json!({ "jams": [1] }) };
+        SocketServer::forward_update(update, &subscriptions, &connections).await;
+
+        let messages = get_queued_messages(&queue).await;
+        assert_eq!(messages.len(), 1);
+        assert_eq!(messages[0]["body"], json!({ "jams": [1] }));
+    }
+
+    #[tokio::test]
+    async fn subscribe_to_unknown_data_type_returns_error() {
+        let update_providers = get_update_providers();
+        let subscriptions = Subscriptions::default();
+        let queue = ClientQueue::new(ServerDiagnostics::default());
+
+        SocketServer::handle_subscribe(1, None, vec![
+            (get_subscription_key("g1", "Unknown"), UpdateMode::Full),
+            (get_subscription_key("g1", "TestStats"), UpdateMode::Full),
+        ], &subscriptions, &queue, &update_providers).await;
+
+        let messages = get_queued_messages(&queue).await;
+        assert_eq!(messages[0]["messageType"], "Error");
+        assert_eq!(messages[0]["error"], "Unknown data type 'Unknown'");
+        assert_eq!(messages[1]["subscriptions"], json!([{ "gameId": "g1", "dataType": "TestStats", "updateMode": "full" }]));
+
+        let subscriptions = subscriptions.read().await;
+        assert!(!subscriptions.contains_key(&get_subscription_key("g1", "Unknown")));
+        assert!(subscriptions.contains_key(&get_subscription_key("g1", "TestStats")));
+    }
+}
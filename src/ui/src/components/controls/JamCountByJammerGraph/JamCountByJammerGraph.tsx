@@ -12,7 +12,12 @@ type JammerInfo = {
     totalScore: number,
     meanNetPerJam: number,
     leadCount: number,
-    meanTimeToInitial: number,
+    leadPercentage: number,
+    lostCount: number,
+    meanTimeToInitial: number | null,
+    bestTimeToInitial: number | null,
+    worstTimeToInitial: number | null,
+    noInitialCount: number,
 }
 
 type UpdateBody = {
@@ -1,26 +1,123 @@
 import React, { useEffect } from "react";
 import { useSearchParams } from "react-router-dom";
 
+type UpdateListener = (update: any) => void;
+
+type Subscription = {
+    dataType: string,
+    gameId: string,
+    listeners: Set<UpdateListener>,
+    lastUpdate?: any,
+};
+
+const INITIAL_RECONNECT_DELAY_MS = 1000;
+const MAX_RECONNECT_DELAY_MS = 30000;
+
 const getSocket = () => new WebSocket(`ws://${location.hostname}:8001/ws`);
 //const getSocket() => new WebSocket(`ws://${location.hostname}:${location.port}/ws`);
 
-const subscribeToState = <TState>(stateType: string, gameId: string, onUpdate: (state: TState) => void) => {
+const subscriptions = new Map<string, Subscription>();
+let sharedSocket: WebSocket | null = null;
+let reconnectDelay = INITIAL_RECONNECT_DELAY_MS;
+let reconnectTimeout: ReturnType<typeof setTimeout> | null = null;
+
+const getSubscriptionKey = (dataType: string, gameId: string) => `${gameId}:${dataType}`;
+
+const getSharedSocket = () => {
+    if(sharedSocket) {
+        return sharedSocket;
+    }
+
+    if(reconnectTimeout) {
+        clearTimeout(reconnectTimeout);
+        reconnectTimeout = null;
+    }
+
     const socket = getSocket();
 
     socket.addEventListener('open', () => {
-        socket.send(JSON.stringify({
-            messageType: "Subscribe",
-            gameId,
-            dataType: stateType
-        }));
+        reconnectDelay = INITIAL_RECONNECT_DELAY_MS;
+
+        if(subscriptions.size > 0) {
+            socket.send(JSON.stringify({
+                messageType: "Subscribe",
+                subscriptions: [...subscriptions.values()].map(({ dataType, gameId }) => ({ dataType, gameId }))
+            }));
+        }
     });
-    
+
     socket.addEventListener('message', (event) => {
-        onUpdate(JSON.parse(event.data));
+        const update = JSON.parse(event.data);
+
+        subscriptions.forEach(subscription => {
+            if(subscription.dataType === update.dataType && (update.gameId === undefined || subscription.gameId === update.gameId)) {
+                subscription.lastUpdate = update;
+                subscription.listeners.forEach(listener => listener(update));
+            }
+        });
+    });
+
+    socket.addEventListener('close', () => {
+        sharedSocket = null;
+
+        if(subscriptions.size > 0 && !reconnectTimeout) {
+            reconnectTimeout = setTimeout(() => {
+                reconnectTimeout = null;
+                getSharedSocket();
+            }, reconnectDelay);
+
+            reconnectDelay = Math.min(reconnectDelay * 2, MAX_RECONNECT_DELAY_MS);
+        }
     });
 
+    sharedSocket = socket;
+
+    return socket;
+}
+
+const sendWhenOpen = (message: object) => {
+    const socket = getSharedSocket();
+
+    if(socket.readyState === WebSocket.OPEN) {
+        socket.send(JSON.stringify(message));
+    }
+}
+
+const subscribeToState = <TState>(stateType: string, gameId: string, onUpdate: (state: TState) => void) => {
+    const key = getSubscriptionKey(stateType, gameId);
+    const listener: UpdateListener = update => onUpdate(update);
+
+    const existingSubscription = subscriptions.get(key);
+
+    if(existingSubscription) {
+        existingSubscription.listeners.add(listener);
+
+        if(existingSubscription.lastUpdate !== undefined) {
+            listener(existingSubscription.lastUpdate);
+        }
+    } else {
+        subscriptions.set(key, { dataType: stateType, gameId, listeners: new Set([listener]) });
+
+        sendWhenOpen({
+            messageType: "Subscribe",
+            gameId,
+            dataType: stateType
+        });
+    }
+
     return () => {
-        socket.close();
+        const subscription = subscriptions.get(key);
+        subscription?.listeners.delete(listener);
+
+        if(subscription?.listeners.size === 0) {
+            subscriptions.delete(key);
+
+            sendWhenOpen({
+                messageType: "Unsubscribe",
+                gameId,
+                dataType: stateType
+            });
+        }
     };
 
 }
@@ -41,7 +138,7 @@ export const useStateSocket = <TState>(stateType: string, onUpdate: (state: TSta
             return;
         }
 
-        subscribeToState(stateType, gameId, onUpdate);
-    
+        return subscribeToState(stateType, gameId, onUpdate);
+
     }, [...(dependencies || []), searchParams])
-};
\ No newline at end of file
+};